@@ -0,0 +1,100 @@
+//! Draws how the maps of the day 5 almanac split & move a range of numbers, one diagram per map,
+//! scaled to the width of the terminal, or of `COLUMNS` when the output isn't a terminal, unless
+//! `--width` is given:
+//! - `almanac <start> <length> [<from> <to>] [--input <file>] [--width <columns>]` follows the
+//!   range from the `from` category to the `to` one, seed & location by default, using the actual
+//!   input unless another one is given
+
+use rusted_reindeer::{
+    day5::{self, diagram},
+    input::{Config, InputStore},
+    range_set::RangeSet,
+};
+use std::{fs, process::ExitCode};
+
+const YEAR: u16 = 2023;
+const DAY: u8 = 5;
+
+struct Options {
+    input: Option<String>,
+    width: usize,
+    positional: Vec<String>,
+}
+
+fn parse_options(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        input: None,
+        width: diagram::terminal_width(),
+        positional: Vec::new(),
+    };
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for {name}"));
+        match arg.as_str() {
+            "--input" => options.input = Some(value("--input")?),
+            "--width" => {
+                let width = value("--width")?;
+                options.width = width
+                    .parse()
+                    .map_err(|_| format!("expected a number of columns, found `{width}`"))?;
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option `{flag}`")),
+            _ => options.positional.push(arg),
+        }
+    }
+    Ok(options)
+}
+
+fn number(text: &str) -> Result<u64, String> {
+    text.parse()
+        .map_err(|_| format!("expected a number, found `{text}`"))
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let (start, length, from, to) = match options.positional.as_slice() {
+        [start, length] => (start, length, "seed", "location"),
+        [start, length, from, to] => (start, length, from.as_str(), to.as_str()),
+        _ => {
+            return Err(
+                "expected the start & length of a range, optionally followed by two categories"
+                    .to_string(),
+            )
+        }
+    };
+    let (start, length) = (number(start)?, number(length)?);
+    let last = length
+        .checked_sub(1)
+        .and_then(|last_offset| start.checked_add(last_offset))
+        .ok_or(format!(
+            "expected a non-empty range that fits in a u64, found {length} numbers from {start}"
+        ))?;
+    let input = if let Some(path) = &options.input {
+        fs::read_to_string(path).map_err(|error| format!("couldn't read {path}: {error}"))?
+    } else {
+        let config = Config::load().map_err(|error| error.to_string())?;
+        InputStore::from_config(&config)
+            .get(YEAR, DAY)
+            .map_err(|error| error.to_string())?
+    };
+    let (_, almanac) = day5::generator(&input).map_err(|error| error.to_string())?;
+    let chain = diagram::render_chain(
+        &almanac,
+        from,
+        to,
+        &RangeSet::from(start..=last),
+        options.width,
+    )
+    .map_err(|error| error.to_string())?;
+    print!("{chain}");
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_options(std::env::args().skip(1)).and_then(|options| run(&options)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
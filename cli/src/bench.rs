@@ -0,0 +1,171 @@
+//! Benchmarks every variant on the actual inputs, keeping the timings in a history file:
+//! - `bench [--runs <n>] [--history <file>] [<day>...]` appends the timings of the current commit
+//! - `bench compare <baseline> [<current>] [--threshold <percent>] [--history <file>]` reports
+//!   changes against the baseline, failing if a variant got significantly slower past the
+//!   threshold (10% by default)
+
+use rusted_reindeer::{
+    bench::{self, History, Record, DEFAULT_HISTORY_FILE},
+    input::{Config, InputStore},
+    solution::Registry,
+};
+use std::{path::PathBuf, process::ExitCode};
+
+const YEAR: u16 = 2023;
+const DEFAULT_RUNS: u32 = 20;
+const DEFAULT_THRESHOLD_PERCENT: f64 = 10.0;
+
+struct Options {
+    history: PathBuf,
+    runs: u32,
+    threshold_percent: f64,
+    positional: Vec<String>,
+}
+
+fn parse_options(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        history: PathBuf::from(DEFAULT_HISTORY_FILE),
+        runs: DEFAULT_RUNS,
+        threshold_percent: DEFAULT_THRESHOLD_PERCENT,
+        positional: Vec::new(),
+    };
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for {name}"));
+        match arg.as_str() {
+            "--history" => options.history = PathBuf::from(value("--history")?),
+            "--runs" => {
+                let runs = value("--runs")?;
+                options.runs = runs
+                    .parse()
+                    .map_err(|_| format!("expected a number of runs, found `{runs}`"))?;
+            }
+            "--threshold" => {
+                let threshold = value("--threshold")?;
+                options.threshold_percent = threshold
+                    .trim_end_matches('%')
+                    .parse()
+                    .map_err(|_| format!("expected a percentage, found `{threshold}`"))?;
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option `{flag}`")),
+            _ => options.positional.push(arg),
+        }
+    }
+    Ok(options)
+}
+
+/// Times the variants of the given days (all by default), skipping the slow ones that don't
+/// complete on actual inputs. Timings include parsing, which every variant has to go through.
+fn run(options: &Options) -> Result<(), String> {
+    let days = options
+        .positional
+        .iter()
+        .map(|day| {
+            day.trim_start_matches("day")
+                .parse::<u8>()
+                .map_err(|_| format!("expected a day, found `{day}`"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let config = Config::load().map_err(|error| error.to_string())?;
+    let inputs = InputStore::from_config(&config);
+    let registry = Registry::new();
+    let commit = bench::current_commit();
+    let mut records = Vec::new();
+    for day in registry.days() {
+        if !days.is_empty() && !days.contains(&day) {
+            continue;
+        }
+        let input = match inputs.get(YEAR, day) {
+            Ok(input) => input,
+            Err(error) => {
+                eprintln!("skipping day{day}: {error}");
+                continue;
+            }
+        };
+        for variant in registry.variants() {
+            if variant.day != day || variant.slow {
+                continue;
+            }
+            let stats = bench::measure(options.runs, || {
+                registry.solve_variant(&variant, &input);
+            })
+            .ok_or("expected at least one run")?;
+            println!(
+                "{variant}: {:.0}ns ± {:.0}ns",
+                stats.mean_ns, stats.std_dev_ns
+            );
+            records.push(Record::new(
+                &commit,
+                variant.day,
+                variant.part,
+                variant.name,
+                &input,
+                stats,
+            ));
+        }
+    }
+    History::append(&options.history, &records).map_err(|error| error.to_string())?;
+    println!(
+        "recorded {} timings for {commit} in {}",
+        records.len(),
+        options.history.display()
+    );
+    Ok(())
+}
+
+/// Whether no variant got significantly slower than the threshold
+fn compare(options: &Options) -> Result<bool, String> {
+    let history = History::load(&options.history).map_err(|error| error.to_string())?;
+    let (baseline, current) = match options.positional.as_slice() {
+        [baseline] => (baseline.clone(), bench::current_commit()),
+        [baseline, current] => (baseline.clone(), current.clone()),
+        _ => return Err("expected a baseline commit, optionally followed by another".to_string()),
+    };
+    let comparisons = history.compare(&baseline, &current);
+    if comparisons.is_empty() {
+        return Err(format!(
+            "no variant was benchmarked on the same input at both {baseline} & {current}, \
+            known commits: {}",
+            history.commits().join(", ")
+        ));
+    }
+    let threshold = options.threshold_percent / 100.0;
+    let mut slowdowns = 0;
+    for comparison in &comparisons {
+        if comparison.is_slowdown(threshold) {
+            slowdowns += 1;
+            println!("SLOWER {comparison}");
+        } else {
+            println!("       {comparison}");
+        }
+    }
+    println!(
+        "{slowdowns} of {} variants got significantly slower by more than {}% since {baseline}",
+        comparisons.len(),
+        options.threshold_percent
+    );
+    Ok(slowdowns == 0)
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+    let is_compare = args.next_if(|arg| arg == "compare").is_some();
+    if !is_compare {
+        args.next_if(|arg| arg == "run");
+    }
+    let result = parse_options(args).and_then(|options| {
+        if is_compare {
+            compare(&options)
+        } else {
+            run(&options).map(|()| true)
+        }
+    });
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::from(2)
+        }
+    }
+}
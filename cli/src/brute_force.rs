@@ -0,0 +1,124 @@
+//! Checks the answer of day 5 part 2 by mapping every seed of the seed ranges, which
+//! `part2_naive` would take hours to do on one core:
+//! - `brute-force [--threads <n>] [--checkpoint <file>] [--input <file>]` goes through the seeds on
+//!   all cores by default, reporting progress as it goes & saving it to the checkpoint file, so
+//!   that a stopped run resumes where it left off. It fails if the answer differs from `part2`'s.
+
+use rusted_reindeer::{
+    day5::{
+        self,
+        brute_force::{self, Checkpoint, DEFAULT_CHECKPOINT_FILE},
+    },
+    input::{Config, InputStore},
+};
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+    thread,
+    time::{Duration, Instant},
+};
+
+const YEAR: u16 = 2023;
+const DAY: u8 = 5;
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+struct Options {
+    threads: usize,
+    checkpoint: PathBuf,
+    input: Option<String>,
+}
+
+fn parse_options(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        threads: thread::available_parallelism().map_or(1, usize::from),
+        checkpoint: PathBuf::from(DEFAULT_CHECKPOINT_FILE),
+        input: None,
+    };
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for {name}"));
+        match arg.as_str() {
+            "--checkpoint" => options.checkpoint = PathBuf::from(value("--checkpoint")?),
+            "--input" => options.input = Some(value("--input")?),
+            "--threads" => {
+                let threads = value("--threads")?;
+                options.threads = threads
+                    .parse()
+                    .ok()
+                    .filter(|threads| *threads > 0)
+                    .ok_or(format!("expected a number of threads, found `{threads}`"))?;
+            }
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    Ok(options)
+}
+
+/// Whether the brute force answer matches `part2`'s
+fn run(options: &Options) -> Result<bool, String> {
+    let input = if let Some(path) = &options.input {
+        fs::read_to_string(path).map_err(|error| format!("couldn't read {path}: {error}"))?
+    } else {
+        let config = Config::load().map_err(|error| error.to_string())?;
+        InputStore::from_config(&config)
+            .get(YEAR, DAY)
+            .map_err(|error| error.to_string())?
+    };
+    let parsed = day5::generator(&input).map_err(|error| error.to_string())?;
+    let mut checkpoint =
+        Checkpoint::load(&options.checkpoint, &input).map_err(|error| error.to_string())?;
+    if !checkpoint.done.is_empty() {
+        eprintln!("resuming from {}", options.checkpoint.display());
+    }
+    let (mut last_report, mut last_save) = (Instant::now(), Instant::now());
+    let mut save_error = None;
+    let brute_force = brute_force::run(
+        &parsed.1,
+        &parsed.0,
+        options.threads,
+        &mut checkpoint,
+        |checkpoint, progress| {
+            if last_report.elapsed() >= REPORT_INTERVAL {
+                eprint!("\r{progress}   ");
+                // Progress is best effort, not worth failing the run over
+                io::stderr().flush().ok();
+                last_report = Instant::now();
+            }
+            if last_save.elapsed() >= SAVE_INTERVAL {
+                save_error = checkpoint
+                    .save(&options.checkpoint)
+                    .err()
+                    .or(save_error.take());
+                last_save = Instant::now();
+            }
+        },
+    );
+    eprintln!();
+    if let Some(error) = save_error {
+        eprintln!("couldn't save progress along the way: {error}");
+    }
+    checkpoint
+        .save(&options.checkpoint)
+        .map_err(|error| error.to_string())?;
+    let brute_force = brute_force.ok_or("expected at least one seed")?;
+    let intervals = day5::part2(&parsed);
+    println!("brute force: {brute_force}, part2: {intervals}");
+    Ok(brute_force == intervals)
+}
+
+fn main() -> ExitCode {
+    match parse_options(std::env::args().skip(1)).and_then(|options| run(&options)) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => {
+            eprintln!("part2 doesn't match the brute force answer");
+            ExitCode::FAILURE
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Lists the day 7 hands from the weakest to the strongest, with what each one wins & why it ranks
+//! where it does:
+//! - `camel-cards [--jokers] [--json] [--input <file>]` ranks the hands as in part 1, or as in part
+//!   2 with `--jokers`, printing a table or JSON, using the actual input unless another one is given
+
+use rusted_reindeer::{
+    day7::{report, CardsConfig},
+    input::{Config, InputStore},
+};
+use std::{fs, process::ExitCode};
+
+const YEAR: u16 = 2023;
+const DAY: u8 = 7;
+
+#[derive(Default)]
+struct Options {
+    jokers: bool,
+    json: bool,
+    input: Option<String>,
+}
+
+fn parse_options(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for {name}"));
+        match arg.as_str() {
+            "--jokers" => options.jokers = true,
+            "--json" => options.json = true,
+            "--input" => options.input = Some(value("--input")?),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    Ok(options)
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let input = if let Some(path) = &options.input {
+        fs::read_to_string(path).map_err(|error| format!("couldn't read {path}: {error}"))?
+    } else {
+        let config = Config::load().map_err(|error| error.to_string())?;
+        InputStore::from_config(&config)
+            .get(YEAR, DAY)
+            .map_err(|error| error.to_string())?
+    };
+    let rules = CardsConfig {
+        j_is_joker: options.jokers,
+    }
+    .rules();
+    let ranking = report::ranking(&input, &rules).map_err(|error| error.to_string())?;
+    if options.json {
+        let json = serde_json::to_string_pretty(&ranking).map_err(|error| error.to_string())?;
+        println!("{json}");
+    } else {
+        print!("{}", report::render_table(&ranking));
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_options(std::env::args().skip(1)).and_then(|options| run(&options)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
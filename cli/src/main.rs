@@ -0,0 +1,77 @@
+//! Solves the puzzles on inputs from the `InputStore`, so that a cached input is all a run needs:
+//! - `rusted-reindeer [<day>...]` runs every variant of both parts of the given days (all by
+//!   default), skipping the brute force ones that don't complete on actual inputs, like
+//!   `part2_naive` of day 5 (see the `brute-force` binary). It fails if an input couldn't be read or
+//!   solved, after going through the other days.
+
+use rusted_reindeer::{
+    input::{Config, InputStore},
+    solution::Registry,
+};
+use std::{process::ExitCode, time::Instant};
+
+const YEAR: u16 = 2023;
+
+fn parse_days(args: impl Iterator<Item = String>) -> Result<Vec<u8>, String> {
+    args.map(|day| {
+        day.trim_start_matches("day")
+            .parse()
+            .map_err(|_| format!("expected a day, found `{day}`"))
+    })
+    .collect()
+}
+
+/// Whether every part of the given days got solved
+fn run(days: &[u8]) -> Result<bool, String> {
+    let config = Config::load().map_err(|error| error.to_string())?;
+    let inputs = InputStore::from_config(&config);
+    let registry = Registry::new();
+    if let Some(day) = days
+        .iter()
+        .find(|day| !registry.days().any(|known| known == **day))
+    {
+        return Err(format!("no solution for day {day}"));
+    }
+    let mut solved = true;
+    for day in registry.days() {
+        if !days.is_empty() && !days.contains(&day) {
+            continue;
+        }
+        println!("Day {day}");
+        let input = match inputs.get(YEAR, day) {
+            Ok(input) => input,
+            Err(error) => {
+                eprintln!("  {error}");
+                solved = false;
+                continue;
+            }
+        };
+        for part in 1..=2 {
+            let start = Instant::now();
+            let answers = registry.solve(day, part, &input, |variant| !variant.slow);
+            // Parsing is shared by the variants of a part, so only the part as a whole gets timed
+            println!("  part {part} ({:.2?})", start.elapsed());
+            for (name, answer) in answers {
+                match answer {
+                    Ok(answer) => println!("    {name}: {answer}"),
+                    Err(error) => {
+                        eprintln!("    {name}: {error}");
+                        solved = false;
+                    }
+                }
+            }
+        }
+    }
+    Ok(solved)
+}
+
+fn main() -> ExitCode {
+    match parse_days(std::env::args().skip(1)).and_then(|days| run(&days)) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::from(2)
+        }
+    }
+}
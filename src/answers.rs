@@ -0,0 +1,340 @@
+use crate::input::InputStore;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.toml";
+
+/// Answers computed by each variant of a part, or the error it failed with
+pub type VariantAnswers = Vec<(&'static str, Result<String, String>)>;
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io { path: PathBuf, source: io::Error },
+    Invalid { path: PathBuf, message: String },
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnswersError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            AnswersError::Invalid { path, message } => write!(f, "{}: {message}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AnswersError::Io { source, .. } => Some(source),
+            AnswersError::Invalid { .. } => None,
+        }
+    }
+}
+
+/// 64-bit FNV-1a of the input, which is stable across platforms & compiler versions unlike
+/// [`std::hash::DefaultHasher`]
+pub fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub input_hash: String,
+}
+
+/// Accepted answers per day & part, each with the hash of the input it was computed on
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnswerRegistry {
+    #[serde(default, rename = "answer")]
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Matched,
+    Mismatched {
+        found: String,
+    },
+    Failed {
+        error: String,
+    },
+    NoVariants,
+    /// Skipped, as the answer can't be expected to hold for a different input
+    InputChanged {
+        current_hash: String,
+    },
+    /// Skipped, as the input isn't available (e.g. not cached in an offline run)
+    InputUnavailable {
+        error: String,
+    },
+}
+
+impl Outcome {
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Mismatched { .. } | Outcome::Failed { .. } | Outcome::NoVariants
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Check<'a> {
+    pub entry: &'a Entry,
+    pub variant: Option<&'static str>,
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for Check<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Entry {
+            day,
+            part,
+            answer,
+            input_hash,
+        } = self.entry;
+        write!(f, "day{day} part{part}")?;
+        if let Some(variant) = self.variant {
+            write!(f, " ({variant})")?;
+        }
+        match &self.outcome {
+            Outcome::Matched => write!(f, ": {answer} as expected"),
+            Outcome::Mismatched { found } => write!(f, ": expected {answer}, found {found}"),
+            Outcome::Failed { error } => write!(f, ": expected {answer}, failed with {error}"),
+            Outcome::NoVariants => write!(f, ": expected {answer}, but no variant is registered"),
+            Outcome::InputChanged { current_hash } => write!(
+                f,
+                ": skipped, answer is for input {input_hash} but current input is {current_hash}"
+            ),
+            Outcome::InputUnavailable { error } => write!(f, ": skipped, {error}"),
+        }
+    }
+}
+
+impl AnswerRegistry {
+    /// Loads the registry at `path`, which is empty if the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(AnswersError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        toml::from_str(&contents).map_err(|error| AnswersError::Invalid {
+            path: path.to_path_buf(),
+            message: error.message().to_string(),
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AnswersError> {
+        let contents = toml::to_string(self).map_err(|error| AnswersError::Invalid {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        })?;
+        fs::write(path, contents).map_err(|source| AnswersError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.day == day && entry.part == part)
+    }
+
+    /// Accepts `answer` for the part, replacing any answer accepted earlier
+    pub fn record(&mut self, day: u8, part: u8, answer: String, input: &str) {
+        let entry = Entry {
+            day,
+            part,
+            answer,
+            input_hash: input_hash(input),
+        };
+        if let Some(existing) = self
+            .entries
+            .iter_mut()
+            .find(|existing| existing.day == day && existing.part == part)
+        {
+            *existing = entry;
+        } else {
+            self.entries.push(entry);
+            self.entries.sort_by_key(|entry| (entry.day, entry.part));
+        }
+    }
+
+    /// Recomputes every registered part with all its variants, as returned by `solve` for
+    /// the day, part & input, skipping parts whose input has changed since they were recorded
+    pub fn verify<S>(&self, year: u16, inputs: &InputStore, mut solve: S) -> Vec<Check<'_>>
+    where
+        S: FnMut(u8, u8, &str) -> VariantAnswers,
+    {
+        let mut checks = Vec::new();
+        for entry in &self.entries {
+            let skipped = |outcome| Check {
+                entry,
+                variant: None,
+                outcome,
+            };
+            let input = match inputs.get(year, entry.day) {
+                Ok(input) => input,
+                Err(error) => {
+                    checks.push(skipped(Outcome::InputUnavailable {
+                        error: error.to_string(),
+                    }));
+                    continue;
+                }
+            };
+            let current_hash = input_hash(&input);
+            if current_hash != entry.input_hash {
+                checks.push(skipped(Outcome::InputChanged { current_hash }));
+                continue;
+            }
+            let variant_answers = solve(entry.day, entry.part, &input);
+            if variant_answers.is_empty() {
+                checks.push(skipped(Outcome::NoVariants));
+            }
+            checks.extend(variant_answers.into_iter().map(|(variant, answer)| Check {
+                entry,
+                variant: Some(variant),
+                outcome: match answer {
+                    Ok(found) if found == entry.answer => Outcome::Matched,
+                    Ok(found) => Outcome::Mismatched { found },
+                    Err(error) => Outcome::Failed { error },
+                },
+            }));
+        }
+        checks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(day: u8, part: u8, input: &str) -> VariantAnswers {
+        let lines = input.lines().count().to_string();
+        match (day, part) {
+            (1, 1) => vec![("part1", Ok(lines))],
+            (1, 2) => vec![
+                ("part2_take1", Ok(lines)),
+                ("part2_take2", Err("line 1, column 1: boom".to_string())),
+            ],
+            (2, 1) => vec![("part1", Ok("-1".to_string()))],
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn input_hash_is_stable() {
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn verify_flags_mismatches_and_skips_changed_inputs() {
+        let cache_dir = tempfile::tempdir().expect("temp dir should be created");
+        let inputs = InputStore::offline(cache_dir.path());
+        fs::create_dir_all(cache_dir.path().join("2023")).expect("year dir should be created");
+        fs::write(inputs.path(2023, 1), "a\nb\n").expect("input should be written");
+        fs::write(inputs.path(2023, 2), "c\n").expect("input should be written");
+
+        let mut registry = AnswerRegistry::default();
+        registry.record(1, 2, "2".to_string(), "a\nb\n");
+        registry.record(1, 1, "3".to_string(), "a\nb\n");
+        registry.record(2, 1, "-1".to_string(), "changed\n");
+        registry.record(3, 1, "4".to_string(), "");
+        let outcomes = registry
+            .verify(2023, &inputs, solve)
+            .into_iter()
+            .map(|check| {
+                (
+                    check.entry.day,
+                    check.entry.part,
+                    check.variant,
+                    check.outcome,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                (
+                    1,
+                    1,
+                    Some("part1"),
+                    Outcome::Mismatched {
+                        found: "2".to_string()
+                    }
+                ),
+                (1, 2, Some("part2_take1"), Outcome::Matched),
+                (
+                    1,
+                    2,
+                    Some("part2_take2"),
+                    Outcome::Failed {
+                        error: "line 1, column 1: boom".to_string()
+                    }
+                ),
+                (
+                    2,
+                    1,
+                    None,
+                    Outcome::InputChanged {
+                        current_hash: input_hash("c\n")
+                    }
+                ),
+                (
+                    3,
+                    1,
+                    None,
+                    Outcome::InputUnavailable {
+                        error: InputStore::offline(cache_dir.path())
+                            .get(2023, 3)
+                            .err()
+                            .map(|error| error.to_string())
+                            .unwrap_or_default()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn registry_round_trips_through_toml() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join(DEFAULT_ANSWERS_FILE);
+        assert_eq!(
+            AnswerRegistry::load(&path).ok(),
+            Some(AnswerRegistry::default())
+        );
+        let mut registry = AnswerRegistry::default();
+        registry.record(5, 2, "46".to_string(), "seeds: 79 14 55 13\n");
+        registry.record(5, 2, "47".to_string(), "seeds: 79 14 55 13\n");
+        registry.save(&path).expect("registry should be saved");
+        let loaded = AnswerRegistry::load(&path).expect("registry should be loaded");
+        assert_eq!(loaded.entries().len(), 1);
+        assert_eq!(
+            loaded.get(5, 2).map(|entry| entry.answer.as_str()),
+            Some("47")
+        );
+        assert_eq!(loaded, registry);
+    }
+}
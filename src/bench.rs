@@ -0,0 +1,528 @@
+//! Benchmark history: timings of every variant are appended to a CSV file, keyed by git commit,
+//! day, part & variant, so that later runs can be compared against a baseline to catch slowdowns
+
+use crate::answers::input_hash;
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub const DEFAULT_HISTORY_FILE: &str = "bench_history.csv";
+
+const HEADER: &str = "timestamp,commit,day,part,variant,input_hash,runs,mean_ns,std_dev_ns";
+
+#[derive(Debug)]
+pub enum BenchError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Invalid {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BenchError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            BenchError::Invalid {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{line}: {message}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for BenchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BenchError::Io { source, .. } => Some(source),
+            BenchError::Invalid { .. } => None,
+        }
+    }
+}
+
+/// Summary of the timings of a number of runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub runs: u32,
+    pub mean_ns: f64,
+    pub std_dev_ns: f64,
+}
+
+impl Stats {
+    #[allow(clippy::cast_precision_loss)] // Timings are nowhere near 2^52 ns
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let runs = u32::try_from(samples.len()).ok().filter(|&runs| runs > 0)?;
+        let samples = samples.iter().map(|sample| sample.as_nanos() as f64);
+        let mean_ns = samples.clone().sum::<f64>() / f64::from(runs);
+        let squares = samples
+            .map(|sample| (sample - mean_ns).powi(2))
+            .sum::<f64>();
+        Some(Stats {
+            runs,
+            mean_ns,
+            std_dev_ns: (squares / f64::from(runs.saturating_sub(1).max(1))).sqrt(),
+        })
+    }
+
+    /// Combines the summaries of several batches of runs, as if all the runs were in one batch
+    pub fn pool(batches: impl IntoIterator<Item = Stats>) -> Option<Self> {
+        let batches = batches.into_iter().collect::<Vec<_>>();
+        let runs = batches.iter().map(|batch| batch.runs).sum::<u32>();
+        if runs == 0 {
+            return None;
+        }
+        let total = |value: fn(&Stats) -> f64| batches.iter().map(value).sum::<f64>();
+        let mean_ns = total(|batch| f64::from(batch.runs) * batch.mean_ns) / f64::from(runs);
+        // Sum of squared deviations from the pooled mean, within & between batches
+        let squares =
+            total(|batch| f64::from(batch.runs.saturating_sub(1)) * batch.std_dev_ns.powi(2))
+                + batches
+                    .iter()
+                    .map(|batch| f64::from(batch.runs) * (batch.mean_ns - mean_ns).powi(2))
+                    .sum::<f64>();
+        Some(Stats {
+            runs,
+            mean_ns,
+            std_dev_ns: (squares / f64::from(runs.saturating_sub(1).max(1))).sqrt(),
+        })
+    }
+
+    /// Whether `other` is slower with 95% confidence, going by a one-sided Welch's t-test
+    pub fn is_significantly_faster_than(&self, other: &Stats) -> bool {
+        let variance_of_mean = |stats: &Stats| stats.std_dev_ns.powi(2) / f64::from(stats.runs);
+        let (ours, theirs) = (variance_of_mean(self), variance_of_mean(other));
+        if ours + theirs == 0.0 {
+            return other.mean_ns > self.mean_ns;
+        }
+        let t = (other.mean_ns - self.mean_ns) / (ours + theirs).sqrt();
+        // Welch–Satterthwaite approximation of the degrees of freedom
+        let degrees_of_freedom = (ours + theirs).powi(2)
+            / (ours.powi(2) / f64::from(self.runs.saturating_sub(1).max(1))
+                + theirs.powi(2) / f64::from(other.runs.saturating_sub(1).max(1)));
+        t > critical_t(degrees_of_freedom)
+    }
+}
+
+/// One-sided 95% critical value of Student's t distribution, rounding the degrees of freedom down
+/// so that it errs on the side of not reporting a slowdown
+fn critical_t(degrees_of_freedom: f64) -> f64 {
+    const TABLE: [(f64, f64); 16] = [
+        (1.0, 6.314),
+        (2.0, 2.920),
+        (3.0, 2.353),
+        (4.0, 2.132),
+        (5.0, 2.015),
+        (6.0, 1.943),
+        (7.0, 1.895),
+        (8.0, 1.860),
+        (9.0, 1.833),
+        (10.0, 1.812),
+        (12.0, 1.782),
+        (15.0, 1.753),
+        (20.0, 1.725),
+        (30.0, 1.697),
+        (60.0, 1.671),
+        (120.0, 1.658),
+    ];
+    if degrees_of_freedom > 1000.0 {
+        return 1.645;
+    }
+    TABLE
+        .iter()
+        .rev()
+        .find(|(df, _)| *df <= degrees_of_freedom)
+        .map_or(TABLE[0].1, |(_, t)| *t)
+}
+
+/// Timings of `runs` calls of `f`, after a first call to warm up caches
+pub fn measure(runs: u32, mut f: impl FnMut()) -> Option<Stats> {
+    f();
+    let samples = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    Stats::from_samples(&samples)
+}
+
+/// Short hash of the checked out commit, suffixed with `-dirty` if there are uncommitted changes,
+/// or `unknown` outside of a git checkout
+pub fn current_commit() -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    match (
+        git(&["rev-parse", "--short=12", "HEAD"]),
+        git(&["status", "--porcelain", "--untracked-files=no"]),
+    ) {
+        (Some(commit), Some(changes)) if !changes.is_empty() => format!("{commit}-dirty"),
+        (Some(commit), _) => commit,
+        (None, _) => "unknown".to_string(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub commit: String,
+    pub day: u8,
+    pub part: u8,
+    pub variant: String,
+    /// Timings are only comparable on the same input
+    pub input_hash: String,
+    pub stats: Stats,
+}
+
+impl Record {
+    pub fn new(commit: &str, day: u8, part: u8, variant: &str, input: &str, stats: Stats) -> Self {
+        Record {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            commit: commit.to_string(),
+            day,
+            part,
+            variant: variant.to_string(),
+            input_hash: input_hash(input),
+            stats,
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{:.0},{:.0}",
+            self.timestamp,
+            self.commit,
+            self.day,
+            self.part,
+            self.variant,
+            self.input_hash,
+            self.stats.runs,
+            self.stats.mean_ns,
+            self.stats.std_dev_ns
+        )
+    }
+
+    fn from_csv(line: &str) -> Result<Self, String> {
+        let fields = line.split(',').collect::<Vec<_>>();
+        let &[timestamp, commit, day, part, variant, input_hash, runs, mean_ns, std_dev_ns] =
+            fields.as_slice()
+        else {
+            return Err(format!("expected 9 fields, found {}", fields.len()));
+        };
+        Ok(Record {
+            timestamp: number("timestamp", timestamp)?,
+            commit: commit.to_string(),
+            day: number("day", day)?,
+            part: number("part", part)?,
+            variant: variant.to_string(),
+            input_hash: input_hash.to_string(),
+            stats: Stats {
+                runs: number("runs", runs)?,
+                mean_ns: number("mean_ns", mean_ns)?,
+                std_dev_ns: number("std_dev_ns", std_dev_ns)?,
+            },
+        })
+    }
+}
+
+fn number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a number for {name}, found `{value}`"))
+}
+
+/// Change in the timings of a variant between two commits
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub day: u8,
+    pub part: u8,
+    pub variant: String,
+    pub baseline: Stats,
+    pub current: Stats,
+}
+
+impl Comparison {
+    /// Relative change of the mean, positive when slower
+    pub fn change(&self) -> f64 {
+        self.current.mean_ns / self.baseline.mean_ns - 1.0
+    }
+
+    pub fn is_significant(&self) -> bool {
+        self.baseline.is_significantly_faster_than(&self.current)
+            || self.current.is_significantly_faster_than(&self.baseline)
+    }
+
+    /// Whether the variant got significantly slower by more than `threshold`, like `0.1` for 10%
+    pub fn is_slowdown(&self, threshold: f64) -> bool {
+        self.change() > threshold && self.baseline.is_significantly_faster_than(&self.current)
+    }
+}
+
+fn format_ns(ns: f64) -> String {
+    match ns {
+        ns if ns >= 1e9 => format!("{:.2}s", ns / 1e9),
+        ns if ns >= 1e6 => format!("{:.2}ms", ns / 1e6),
+        ns if ns >= 1e3 => format!("{:.2}µs", ns / 1e3),
+        ns => format!("{ns:.0}ns"),
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day{} part{} ({}): {} -> {} ({:+.1}%{})",
+            self.day,
+            self.part,
+            self.variant,
+            format_ns(self.baseline.mean_ns),
+            format_ns(self.current.mean_ns),
+            self.change() * 100.0,
+            if self.is_significant() {
+                ""
+            } else {
+                ", not significant"
+            }
+        )
+    }
+}
+
+/// Every benchmark run so far, in the order they were appended
+#[derive(Debug, Default, PartialEq)]
+pub struct History {
+    records: Vec<Record>,
+}
+
+impl History {
+    /// Loads the history at `path`, which is empty if the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self, BenchError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(BenchError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        let records = contents
+            .lines()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                Record::from_csv(line).map_err(|message| BenchError::Invalid {
+                    path: path.to_path_buf(),
+                    line: index + 1,
+                    message,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(History { records })
+    }
+
+    /// Appends `records` to the history at `path`, creating it if needed
+    pub fn append(path: &Path, records: &[Record]) -> Result<(), BenchError> {
+        let io_error = |source| BenchError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(io_error)?;
+        let mut contents = String::new();
+        if is_new {
+            contents.push_str(HEADER);
+            contents.push('\n');
+        }
+        for record in records {
+            contents.push_str(&record.to_csv());
+            contents.push('\n');
+        }
+        file.write_all(contents.as_bytes()).map_err(io_error)
+    }
+
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Commits with recorded runs, in the order they were first benchmarked
+    pub fn commits(&self) -> Vec<&str> {
+        let mut commits = Vec::new();
+        for record in &self.records {
+            if !commits.contains(&record.commit.as_str()) {
+                commits.push(&record.commit);
+            }
+        }
+        commits
+    }
+
+    /// Timings of the variants benchmarked at both commits on the same input, with all the runs
+    /// of a commit pooled together
+    pub fn compare(&self, baseline: &str, current: &str) -> Vec<Comparison> {
+        let mut keys = Vec::new();
+        for record in &self.records {
+            let key = (record.day, record.part, &record.variant, &record.input_hash);
+            if record.commit == current && !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys.sort();
+        keys.into_iter()
+            .filter_map(|(day, part, variant, input_hash)| {
+                let stats = |commit: &str| {
+                    Stats::pool(
+                        self.records
+                            .iter()
+                            .filter(|record| {
+                                record.commit == commit
+                                    && (record.day, record.part) == (day, part)
+                                    && &record.variant == variant
+                                    && &record.input_hash == input_hash
+                            })
+                            .map(|record| record.stats),
+                    )
+                };
+                Some(Comparison {
+                    day,
+                    part,
+                    variant: variant.clone(),
+                    baseline: stats(baseline)?,
+                    current: stats(current)?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(runs: u32, mean_ns: f64, std_dev_ns: f64) -> Stats {
+        Stats {
+            runs,
+            mean_ns,
+            std_dev_ns,
+        }
+    }
+
+    fn record(commit: &str, variant: &str, input: &str, stats: Stats) -> Record {
+        Record {
+            timestamp: 1_701_388_800,
+            ..Record::new(commit, 1, 2, variant, input, stats)
+        }
+    }
+
+    #[test]
+    fn stats_of_samples_and_pooled_batches() {
+        let samples = [2, 4, 4, 4, 5, 5, 7, 9].map(Duration::from_nanos);
+        let all = Stats::from_samples(&samples).expect("there are samples");
+        assert_eq!(all.runs, 8);
+        assert!((all.mean_ns - 5.0).abs() < 1e-9);
+        assert!((all.std_dev_ns - (32.0_f64 / 7.0).sqrt()).abs() < 1e-9);
+
+        let halves = samples
+            .chunks(4)
+            .map(|half| Stats::from_samples(half).expect("there are samples"));
+        let pooled = Stats::pool(halves).expect("there are runs");
+        assert_eq!(pooled.runs, all.runs);
+        assert!((pooled.mean_ns - all.mean_ns).abs() < 1e-9);
+        assert!((pooled.std_dev_ns - all.std_dev_ns).abs() < 1e-9);
+
+        assert_eq!(Stats::from_samples(&[]), None);
+        assert_eq!(Stats::pool([]), None);
+    }
+
+    #[test]
+    fn only_significant_slowdowns_past_the_threshold_are_reported() {
+        let baseline = stats(20, 1000.0, 50.0);
+        let compare = |current| Comparison {
+            day: 1,
+            part: 2,
+            variant: "part2_take2".to_string(),
+            baseline,
+            current,
+        };
+        let slower = compare(stats(20, 1200.0, 50.0));
+        assert!(slower.is_significant());
+        assert!(slower.is_slowdown(0.1));
+        assert!(!slower.is_slowdown(0.25));
+        assert_eq!(
+            slower.to_string(),
+            "day1 part2 (part2_take2): 1.00µs -> 1.20µs (+20.0%)"
+        );
+
+        let noisy = compare(stats(3, 1200.0, 500.0));
+        assert!(!noisy.is_significant());
+        assert!(!noisy.is_slowdown(0.1));
+        assert_eq!(
+            noisy.to_string(),
+            "day1 part2 (part2_take2): 1.00µs -> 1.20µs (+20.0%, not significant)"
+        );
+
+        let faster = compare(stats(20, 500.0, 50.0));
+        assert!(faster.is_significant());
+        assert!(!faster.is_slowdown(0.1));
+    }
+
+    #[test]
+    fn history_round_trips_and_compares_on_the_same_input() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join(DEFAULT_HISTORY_FILE);
+        assert_eq!(History::load(&path).ok(), Some(History::default()));
+
+        let records = [
+            record("aaa", "part2_take1", "input", stats(10, 10_000.0, 100.0)),
+            record("aaa", "part2_take2", "input", stats(10, 1000.0, 10.0)),
+            record("bbb", "part2_take2", "input", stats(10, 1500.0, 10.0)),
+            record("bbb", "part2_take2", "other input", stats(10, 900.0, 10.0)),
+        ];
+        History::append(&path, &records[..2]).expect("history should be written");
+        History::append(&path, &records[2..]).expect("history should be appended to");
+        let history = History::load(&path).expect("history should be read");
+        assert_eq!(history.records(), records);
+        assert_eq!(history.commits(), ["aaa", "bbb"]);
+
+        let comparisons = history.compare("aaa", "bbb");
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(comparisons[0].variant, "part2_take2");
+        assert!(comparisons[0].is_slowdown(0.1));
+
+        fs::write(
+            &path,
+            format!("{HEADER}\n1,aaa,1,2,part2_take1,0,ten,1,1\n"),
+        )
+        .expect("history should be written");
+        assert_eq!(
+            History::load(&path).map_err(|error| error.to_string()),
+            Err(format!(
+                "{}:2: expected a number for runs, found `ten`",
+                path.display()
+            ))
+        );
+    }
+}
@@ -1,23 +1,67 @@
-fn extract_calibration_value(sentence: &str) -> u32 {
+use crate::{
+    parse::{self, Location},
+    solution::{variants, Solution, Variant},
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    NonAsciiCharacter { location: Location, found: char },
+    MissingDigit { location: Location },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::NonAsciiCharacter { location, found } => {
+                write!(
+                    f,
+                    "{location}: expected an ASCII character, found `{found}`"
+                )
+            }
+            ParseError::MissingDigit { location } => {
+                write!(f, "{location}: expected a digit somewhere in the line")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Sums the calibration values extracted from each line, failing on the first line without one
+fn sum_calibration_values(
+    input: &str,
+    extract_calibration_value: fn(&str) -> Option<u32>,
+) -> Result<u32, ParseError> {
+    parse::lines(input)
+        .map(|line| {
+            if let Some((offset, found)) = line.text.char_indices().find(|(_, c)| !c.is_ascii()) {
+                return Err(ParseError::NonAsciiCharacter {
+                    location: line.at_byte(offset),
+                    found,
+                });
+            }
+            extract_calibration_value(line.text).ok_or(ParseError::MissingDigit {
+                location: line.start(),
+            })
+        })
+        .sum()
+}
+
+fn extract_calibration_value(sentence: &str) -> Option<u32> {
     let first_digit = sentence
         .chars()
-        .find(|c| c.is_ascii_digit())
-        .and_then(|c| c.to_digit(10))
-        .expect("There should be a digit in the sentence");
+        .find(char::is_ascii_digit)
+        .and_then(|c| c.to_digit(10))?;
     let last_digit = sentence
         .chars()
-        .rfind(|c| c.is_ascii_digit())
-        .and_then(|c| c.to_digit(10))
-        .expect("There should be a digit in the sentence");
-    (first_digit * 10) + last_digit
+        .rfind(char::is_ascii_digit)
+        .and_then(|c| c.to_digit(10))?;
+    Some((first_digit * 10) + last_digit)
 }
 
-pub fn part1(input: &str) -> u32 {
-    input
-        .split_terminator('\n')
-        // .filter(|s| !s.is_empty())
-        .map(extract_calibration_value)
-        .sum()
+pub fn part1(input: &str) -> Result<u32, ParseError> {
+    sum_calibration_values(input, extract_calibration_value)
 }
 
 const DIGIT_WORDS: [&str; 10] = [
@@ -30,7 +74,7 @@ const DIGIT_WORDS: [&str; 10] = [
 
 const DIGIT_CHARS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
 
-fn extract_calibration_value_strings_take1_fixed(sentence: &str) -> u32 {
+fn extract_calibration_value_strings_take1_fixed(sentence: &str) -> Option<u32> {
     let mut first_index = sentence.len();
     let mut last_index = 0;
     let mut first_num = 0;
@@ -61,49 +105,39 @@ fn extract_calibration_value_strings_take1_fixed(sentence: &str) -> u32 {
                 }
             });
     }
+    if first_num == 0 {
+        return None; // Only 1..=9 are looked for, so 0 means nothing was found
+    }
     let calibration_value = 10 * first_num + last_num;
     // println!("{}: {}", sentence, calibration_value);
-    calibration_value as u32
+    Some(calibration_value as u32)
 }
 
-pub fn part2_take1(input: &str) -> u32 {
-    input
-        .split_terminator('\n')
-        .map(extract_calibration_value_strings_take1_fixed)
-        .sum()
+pub fn part2_take1(input: &str) -> Result<u32, ParseError> {
+    sum_calibration_values(input, extract_calibration_value_strings_take1_fixed)
 }
 
-fn extract_calibration_value_strings_take2(sentence: &str) -> u32 {
-    let first_digit = sentence
-        .chars()
-        .enumerate()
-        .find_map(|(i, c)| {
-            c.to_digit(10)
-                .filter(|i| *i != 0)
-                .or_else(|| starts_with_digit_word(sentence, i))
-        })
-        .expect("There should be a digit in the sentence");
+fn extract_calibration_value_strings_take2(sentence: &str) -> Option<u32> {
+    let first_digit = sentence.chars().enumerate().find_map(|(i, c)| {
+        c.to_digit(10)
+            .filter(|i| *i != 0)
+            .or_else(|| starts_with_digit_word(sentence, i))
+    })?;
     let len = sentence.len();
-    let last_digit = sentence
-        .chars()
-        .rev()
-        .enumerate()
-        .find_map(|(i, c)| {
-            c.to_digit(10)
-                .filter(|i| *i != 0)
-                .or_else(|| ends_with_digit_word(sentence, len - i - 1))
-        })
-        .expect("There should be a digit in the sentence");
+    let last_digit = sentence.chars().rev().enumerate().find_map(|(i, c)| {
+        c.to_digit(10)
+            .filter(|i| *i != 0)
+            .or_else(|| ends_with_digit_word(sentence, len - i - 1))
+    })?;
     // let calibration_value = 10 * first_digit + last_digit;
     // println!("{}: {}", sentence, calibration_value);
     // calibration_value
-    (first_digit * 10) + last_digit
+    Some((first_digit * 10) + last_digit)
 }
 
+/// Expects an ASCII `sentence`, so that char indices are the same as byte indices
 fn starts_with_digit_word(sentence: &str, index: usize) -> Option<u32> {
-    let substring = sentence
-        .get(index..sentence.len())
-        .expect("string should be single length characters");
+    let substring = sentence.get(index..sentence.len())?;
     DIGIT_WORDS
         .iter()
         .enumerate()
@@ -112,10 +146,9 @@ fn starts_with_digit_word(sentence: &str, index: usize) -> Option<u32> {
         .map(|(num, _)| num as u32)
 }
 
+/// Expects an ASCII `sentence`, so that char indices are the same as byte indices
 fn ends_with_digit_word(sentence: &str, index: usize) -> Option<u32> {
-    let substring = sentence
-        .get(0..=index)
-        .expect("string should be single length characters");
+    let substring = sentence.get(0..=index)?;
     DIGIT_WORDS
         .iter()
         .enumerate()
@@ -124,13 +157,28 @@ fn ends_with_digit_word(sentence: &str, index: usize) -> Option<u32> {
         .map(|(num, _)| num as u32)
 }
 
-/// Went for take_2 as take_1 wasn't working as I was looking for find_indices.
-/// Later found match_indices, but still take_2 is ~10x faster than take_1
-pub fn part2_take2(input: &str) -> u32 {
-    input
-        .split_terminator('\n')
-        .map(extract_calibration_value_strings_take2)
-        .sum()
+/// Went for `take_2` as `take_1` wasn't working as I was looking for `find_indices`.
+/// Later found `match_indices`, but still `take_2` is ~10x faster than `take_1`
+pub fn part2_take2(input: &str) -> Result<u32, ParseError> {
+    sum_calibration_values(input, extract_calibration_value_strings_take2)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 1;
+    type Parsed = String;
+    type Answer = u32;
+    type Error = ParseError;
+    const PARTS: [&'static [Variant<Self>]; 2] = [
+        variants![fallible: part1],
+        variants![fallible: part2_take1, part2_take2],
+    ];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error> {
+        // Parts parse lines themselves, as what counts as a digit differs between them
+        Ok(input.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -146,10 +194,10 @@ mod tests {
             a1b2c3d4e5f
             treb7uchet
         "};
-        assert_eq!(part1(input), 142);
+        assert_eq!(part1(input), Ok(142));
     }
 
-    fn part2(input: &str) -> u32 {
+    fn part2(input: &str) -> Result<u32, ParseError> {
         // part2_take1(input)
         part2_take2(input)
     }
@@ -165,39 +213,59 @@ mod tests {
             zoneight234
             7pqrstsixteen
         "};
-        assert_eq!(part2(input), 281);
+        assert_eq!(part2(input), Ok(281));
     }
 
     #[test]
     fn part2_single_digit_case() {
-        assert_eq!(part2("treb7uchet"), 77);
-        assert_eq!(part2("4l"), 44);
-        assert_eq!(part2("e9"), 99);
-        assert_eq!(part2("1"), 11);
-        assert_eq!(part2("two"), 22);
+        assert_eq!(part2("treb7uchet"), Ok(77));
+        assert_eq!(part2("4l"), Ok(44));
+        assert_eq!(part2("e9"), Ok(99));
+        assert_eq!(part2("1"), Ok(11));
+        assert_eq!(part2("two"), Ok(22));
     }
 
     #[test]
     fn part2_tricky_case() {
-        assert_eq!(part2("2twone"), 21);
+        assert_eq!(part2("2twone"), Ok(21));
     }
 
     #[test]
     fn part2_ending_chars_case() {
-        assert_eq!(part2("2three1"), 21);
-        assert_eq!(part2("42one35"), 45);
+        assert_eq!(part2("2three1"), Ok(21));
+        assert_eq!(part2("42one35"), Ok(45));
     }
 
     #[test]
     fn part2_repeating_digit_case() {
-        assert_eq!(part2("2three2"), 22);
-        assert_eq!(part2("four123four"), 44);
+        assert_eq!(part2("2three2"), Ok(22));
+        assert_eq!(part2("four123four"), Ok(44));
     }
 
     #[test]
     fn part2_zero_case() {
-        assert_eq!(part2("012zero"), 12);
-        assert_eq!(part2("zero120"), 12);
+        assert_eq!(part2("012zero"), Ok(12));
+        assert_eq!(part2("zero120"), Ok(12));
     }
 
+    #[test]
+    fn missing_digit_error() {
+        let input = indoc! {"
+            1abc2
+            pqrstuvwx
+        "};
+        let expected = Err(ParseError::MissingDigit {
+            location: Location { line: 2, column: 1 },
+        });
+        assert_eq!(part1(input), expected);
+        assert_eq!(part2_take1(input), expected);
+        assert_eq!(part2_take2(input), expected);
+        assert_eq!(
+            part2("tw\u{f6}ne"),
+            Err(ParseError::NonAsciiCharacter {
+                location: Location { line: 1, column: 3 },
+                found: '\u{f6}',
+            })
+        );
+    }
 }
@@ -1,33 +1,34 @@
-use std::{
-    collections::{HashMap, HashSet},
-    iter::successors,
+use std::{fmt, iter::successors};
+
+use crate::{
+    grid::{Direction, Grid, GridError, Position},
+    parse::{self, Location},
+    solution::{variants, Solution, Variant},
 };
 
 use itertools::Itertools;
 use strum::{EnumIter, IntoEnumIterator};
 
-type Coordinate = (i32, i32);
-
 #[derive(Debug)]
 pub struct Map {
-    tiles: HashMap<Coordinate, Tile>,
-    animal_coords: Coordinate,
-    max_coords: Coordinate,
-}
-
-#[derive(Debug)]
-pub struct Tile {
-    coordinate: Coordinate,
-    tile_type: TileType,
+    tiles: Grid<TileType>,
+    /// Pipe that fits under the animal, going by the two pipes that connect to it
+    animal_pipe: Pipe,
+    /// Whether each tile is part of the loop going through the animal
+    main_loop: Grid<bool>,
 }
 
-impl Tile {
-    fn connecting_coords_if_pipe(&self) -> Option<[Coordinate; 2]> {
-        match &self.tile_type {
-            TileType::Pipe(pipe) => Some(pipe.connecting_coords(self.coordinate)),
-            _ => None,
-        }
-    }
+/// Positions on the map that the pipe at `position` connects to, if there's a pipe there
+fn connecting_positions(
+    tiles: &Grid<TileType>,
+    position: Position,
+) -> impl Iterator<Item = Position> + '_ {
+    tiles
+        .get(position)
+        .and_then(TileType::connecting_directions_if_pipe)
+        .into_iter()
+        .flatten()
+        .filter_map(move |direction| tiles.step(position, direction))
 }
 
 #[derive(Debug)]
@@ -38,11 +39,18 @@ pub enum TileType {
 }
 
 impl TileType {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '.' => Self::Ground,
-            'S' => Self::Animal,
-            other => Self::Pipe(Pipe::from_char(other)),
+            '.' => Some(Self::Ground),
+            'S' => Some(Self::Animal),
+            other => Pipe::from_char(other).map(Self::Pipe),
+        }
+    }
+
+    fn connecting_directions_if_pipe(&self) -> Option<[Direction; 2]> {
+        match self {
+            TileType::Pipe(pipe) => Some(pipe.connecting_directions()),
+            _ => None,
         }
     }
 }
@@ -58,26 +66,26 @@ pub enum Pipe {
 }
 
 impl Pipe {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '|' => Self::Vertical,
-            '-' => Self::Horizontal,
-            'L' => Self::BottomLeft,
-            'J' => Self::BottomRight,
-            '7' => Self::TopRight,
-            'F' => Self::TopLeft,
-            _ => unreachable!(),
+            '|' => Some(Self::Vertical),
+            '-' => Some(Self::Horizontal),
+            'L' => Some(Self::BottomLeft),
+            'J' => Some(Self::BottomRight),
+            '7' => Some(Self::TopRight),
+            'F' => Some(Self::TopLeft),
+            _ => None,
         }
     }
 
-    fn from_connecting_directions(directions: [Direction; 2]) -> Self {
-        Pipe::iter()
-            .find(|pipe| {
-                let connecting_directions = &pipe.connecting_directions();
-                connecting_directions.contains(&directions[0])
-                    && connecting_directions.contains(&directions[1])
-            })
-            .expect("there must exist a pipe connecting two pipes in a loop")
+    /// The pipe connecting both directions, unless they're the same one
+    fn from_connecting_directions(directions: [Direction; 2]) -> Option<Self> {
+        Pipe::iter().find(|pipe| {
+            let connecting_directions = &pipe.connecting_directions();
+            directions[0] != directions[1]
+                && connecting_directions.contains(&directions[0])
+                && connecting_directions.contains(&directions[1])
+        })
     }
 
     fn connecting_directions(&self) -> [Direction; 2] {
@@ -90,147 +98,204 @@ impl Pipe {
             Pipe::TopRight => [Direction::Left, Direction::Down],
         }
     }
-
-    fn connecting_coords(&self, coordinate: Coordinate) -> [Coordinate; 2] {
-        let [d1, d2] = self.connecting_directions();
-        [d1.next_coord(coordinate), d2.next_coord(coordinate)]
-    }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
-pub enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    UnknownTile {
+        location: Location,
+        found: char,
+    },
+    EmptyLine {
+        location: Location,
+    },
+    RaggedLine {
+        location: Location,
+        expected_width: usize,
+        found_width: usize,
+    },
+    MissingAnimal {
+        location: Location,
+    },
+    DuplicateAnimal {
+        location: Location,
+        first: Location,
+    },
+    UnconnectedAnimal {
+        location: Location,
+        connecting_pipes: usize,
+    },
+    OpenLoop {
+        location: Location,
+    },
 }
 
-impl Direction {
-    fn next_coord(self, (x, y): Coordinate) -> Coordinate {
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Direction::Left => (x - 1, y),
-            Direction::Right => (x + 1, y),
-            Direction::Up => (x, y - 1),
-            Direction::Down => (x, y + 1),
+            ParseError::UnknownTile { location, found } => {
+                write!(f, "{location}: expected one of `|-LJ7F.S`, found `{found}`")
+            }
+            ParseError::EmptyLine { location } => {
+                write!(f, "{location}: expected a non-empty line of tiles")
+            }
+            ParseError::RaggedLine {
+                location,
+                expected_width,
+                found_width,
+            } => write!(
+                f,
+                "{location}: expected {expected_width} tiles like the first line, found {found_width}"
+            ),
+            ParseError::MissingAnimal { location } => {
+                write!(f, "{location}: expected an `S` somewhere in the map")
+            }
+            ParseError::DuplicateAnimal { location, first } => {
+                write!(f, "{location}: expected a single `S`, but there's already one at {first}")
+            }
+            ParseError::UnconnectedAnimal {
+                location,
+                connecting_pipes,
+            } => write!(
+                f,
+                "{location}: expected 2 pipes connecting to the `S`, found {connecting_pipes}"
+            ),
+            ParseError::OpenLoop { location } => {
+                write!(f, "{location}: expected the pipes from the `S` to loop back to each other")
+            }
         }
     }
 }
 
-#[allow(clippy::cast_possible_wrap)]
-pub fn generator(input: &str) -> Map {
-    let (mut x_max, mut y_max) = (0, 0);
-    let (animal_coords, tiles) = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            x_max = line.len() - 1;
-            y_max = y;
-            line.chars().enumerate().map(move |(x, char)| {
-                let coord = (x as i32, y as i32);
-                (
-                    coord,
-                    Tile {
-                        coordinate: coord,
-                        tile_type: TileType::from_char(char),
-                    },
-                )
-            })
-        })
-        .fold(
-            ((0, 0), HashMap::new()),
-            |(animal_coord, mut map), (coord, tile)| {
-                let animal_coord = if matches!(tile.tile_type, TileType::Animal) {
-                    coord
-                } else {
-                    animal_coord
-                };
-                map.insert(coord, tile);
-                (animal_coord, map)
+impl std::error::Error for ParseError {}
+
+impl From<GridError<ParseError>> for ParseError {
+    fn from(error: GridError<ParseError>) -> Self {
+        match error {
+            GridError::EmptyLine { location } => ParseError::EmptyLine { location },
+            GridError::RaggedLine {
+                location,
+                expected_width,
+                found_width,
+            } => ParseError::RaggedLine {
+                location,
+                expected_width,
+                found_width,
             },
-        );
-    let max_coords = (x_max as i32, y_max as i32);
-    Map {
-        tiles,
-        animal_coords,
-        max_coords,
+            GridError::Cell(error) => error,
+        }
     }
 }
 
-fn trace_main_loop(map: &Map) -> HashSet<Coordinate> {
-    let (_, animal_connecting_pipes_coords): (Vec<_>, Vec<_>) =
-        get_connecting_pipes(map.animal_coords, map)
-            .iter()
-            .copied()
-            .unzip();
-
-    let mut visited: HashSet<Coordinate> = HashSet::new();
-    visited.insert(map.animal_coords);
-    visited.extend(animal_connecting_pipes_coords.iter());
-
-    successors(Some(animal_connecting_pipes_coords), |coords| {
-        if coords.len() == 2 && coords[0] == coords[1] {
-            None
-        } else {
-            let next_connecting_pipes = coords
-                .iter()
-                .filter_map(|coord| {
-                    map.tiles
-                        .get(coord)
-                        .and_then(Tile::connecting_coords_if_pipe)
-                })
-                .flatten()
-                .filter(|coord| {
-                    matches!(
-                        map.tiles.get(coord).map(|tile| &tile.tile_type),
-                        Some(TileType::Pipe(_))
-                    )
-                })
-                .filter(|coord| !visited.contains(coord))
-                .collect_vec();
-            visited.extend(next_connecting_pipes.iter());
-            // print!("{next_pipes:?}\n\n");
-            Some(next_connecting_pipes)
+pub fn generator(input: &str) -> Result<Map, ParseError> {
+    let mut animal: Option<Location> = None;
+    let tiles = Grid::parse(input, |char, location| {
+        let tile_type = TileType::from_char(char).ok_or(ParseError::UnknownTile {
+            location,
+            found: char,
+        })?;
+        if matches!(tile_type, TileType::Animal) {
+            if let Some(first) = animal {
+                return Err(ParseError::DuplicateAnimal { location, first });
+            }
+            animal = Some(location);
         }
+        Ok(tile_type)
+    })?;
+    let (Some((animal_position, _)), Some(animal_location)) = (
+        tiles
+            .iter()
+            .find(|(_, tile_type)| matches!(tile_type, TileType::Animal)),
+        animal,
+    ) else {
+        return Err(ParseError::MissingAnimal {
+            location: parse::end_of_input(input),
+        });
+    };
+    let connecting_pipes = get_connecting_pipes(animal_position, &tiles);
+    let unconnected = ParseError::UnconnectedAnimal {
+        location: animal_location,
+        connecting_pipes: connecting_pipes.len(),
+    };
+    let [(first_direction, first_position), (second_direction, second_position)] =
+        connecting_pipes[..]
+    else {
+        return Err(unconnected);
+    };
+    let animal_pipe =
+        Pipe::from_connecting_directions([first_direction, second_direction]).ok_or(unconnected)?;
+    let main_loop = trace_main_loop(&tiles, animal_position, [first_position, second_position])
+        .ok_or(ParseError::OpenLoop {
+            location: animal_location,
+        })?;
+    Ok(Map {
+        tiles,
+        animal_pipe,
+        main_loop,
     })
-    .last();
-    visited
 }
 
-fn get_connecting_pipes(loop_coords: Coordinate, map: &Map) -> [(Direction, Coordinate); 2] {
-    let neighbours = [
-        Direction::Left,
-        Direction::Right,
-        Direction::Up,
-        Direction::Down,
-    ]
-    .iter()
-    .map(|direction| (*direction, direction.next_coord(loop_coords)))
-    .filter(|(_, next_coord)| {
-        map.tiles
-            .get(next_coord)
-            .and_then(Tile::connecting_coords_if_pipe)
-            .filter(|coords| coords.contains(&loop_coords))
-            .is_some()
-    })
-    .collect_vec();
-    assert_eq!(neighbours.len(), 2);
-    [neighbours[0], neighbours[1]]
+/// Tiles of the loop that goes through the animal, walking it both ways from the pipes connecting
+/// to the animal until both ends meet, or `None` if they run out of pipes before meeting
+fn trace_main_loop(
+    tiles: &Grid<TileType>,
+    animal_position: Position,
+    animal_connecting_pipes_positions: [Position; 2],
+) -> Option<Grid<bool>> {
+    let mut visited = Grid::new(tiles.width(), tiles.height(), false);
+    visited[animal_position] = true;
+    for position in &animal_connecting_pipes_positions {
+        visited[*position] = true;
+    }
+
+    let last = successors(
+        Some(animal_connecting_pipes_positions.to_vec()),
+        |positions| {
+            if positions.is_empty() || positions.len() == 2 && positions[0] == positions[1] {
+                None
+            } else {
+                let next_connecting_pipes = positions
+                    .iter()
+                    .flat_map(|position| connecting_positions(tiles, *position))
+                    .filter(|position| matches!(tiles.get(*position), Some(TileType::Pipe(_))))
+                    .filter(|position| !visited[*position])
+                    .collect_vec();
+                for position in &next_connecting_pipes {
+                    visited[*position] = true;
+                }
+                Some(next_connecting_pipes)
+            }
+        },
+    )
+    .last()?;
+    (!last.is_empty()).then_some(visited)
 }
 
-pub fn part1(map: &Map) -> u32 {
-    (trace_main_loop(map).len() / 2) as u32
+/// Directions & positions of the pipes next to `loop_position` that connect to it
+fn get_connecting_pipes(
+    loop_position: Position,
+    tiles: &Grid<TileType>,
+) -> Vec<(Direction, Position)> {
+    Direction::ALL
+        .iter()
+        .filter_map(|direction| {
+            tiles
+                .step(loop_position, *direction)
+                .map(|next_position| (*direction, next_position))
+        })
+        .filter(|(_, next_position)| {
+            connecting_positions(tiles, *next_position).any(|position| position == loop_position)
+        })
+        .collect_vec()
 }
 
-fn infer_animal_pipe(map: &Map) -> Pipe {
-    let (animal_connecting_pipe_directions, _): (Vec<_>, Vec<_>) =
-        get_connecting_pipes(map.animal_coords, map)
-            .iter()
-            .copied()
-            .unzip();
-    Pipe::from_connecting_directions([
-        animal_connecting_pipe_directions[0],
-        animal_connecting_pipe_directions[1],
-    ])
+pub fn part1(map: &Map) -> u32 {
+    let main_loop_length = map
+        .main_loop
+        .iter()
+        .filter(|(_, is_loop_tile)| **is_loop_tile)
+        .count();
+    (main_loop_length / 2) as u32
 }
 
 /// Uses ray casting to count the points inside the main loop.
@@ -245,20 +310,19 @@ fn infer_animal_pipe(map: &Map) -> Pipe {
 ///  outside L---7 inside
 /// ```
 fn count_points_inside_main_loop(map: &Map) -> u32 {
-    let main_loop_coords = trace_main_loop(map);
-    let (x_max, y_max) = map.max_coords;
+    let main_loop = &map.main_loop;
+    let animal_pipe = &map.animal_pipe;
     let mut num_inside_points = 0;
     // row to cast ray
-    for y in 0..=y_max {
+    for y in 0..map.tiles.height() {
         let mut inside = false;
         // ray casting
-        for x in 0..=x_max {
-            let coords = (x, y);
-            let is_loop_tile = main_loop_coords.contains(&coords);
-            let tile_type = &map.tiles[&coords].tile_type;
+        for x in 0..map.tiles.width() {
+            let position = Position::new(x, y);
+            let is_loop_tile = main_loop[position];
+            let tile_type = &map.tiles[position];
             match tile_type {
                 TileType::Animal => {
-                    let animal_pipe = infer_animal_pipe(map);
                     if animal_pipe.connecting_directions().contains(&Direction::Up) {
                         // intersection
                         inside = !inside;
@@ -285,6 +349,20 @@ pub fn part2(map: &Map) -> u32 {
     count_points_inside_main_loop(map)
 }
 
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 10;
+    type Parsed = Map;
+    type Answer = u32;
+    type Error = ParseError;
+    const PARTS: [&'static [Variant<Self>]; 2] = [variants![part1], variants![part2]];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error> {
+        generator(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -311,8 +389,8 @@ mod tests {
     fn part1_example() {
         // println!("{:?}", generator(EXAMPLE_INPUT_1));
         // println!("{:?}", generator(EXAMPLE_INPUT_2));
-        assert_eq!(part1(&generator(EXAMPLE_INPUT_1)), 4);
-        assert_eq!(part1(&generator(EXAMPLE_INPUT_2)), 8);
+        assert_eq!(generator(EXAMPLE_INPUT_1).map(|map| part1(&map)), Ok(4));
+        assert_eq!(generator(EXAMPLE_INPUT_2).map(|map| part1(&map)), Ok(8));
     }
 
     #[test]
@@ -324,7 +402,7 @@ mod tests {
             .L-J.
             .....
         "};
-        assert_eq!(part2(&generator(map)), 1);
+        assert_eq!(generator(map).map(|map| part2(&map)), Ok(1));
 
         let map = indoc! {"
             ...........
@@ -337,7 +415,7 @@ mod tests {
             .L--J.L--J.
             ...........
         "};
-        assert_eq!(part2(&generator(map)), 4);
+        assert_eq!(generator(map).map(|map| part2(&map)), Ok(4));
 
         let map = indoc! {"
             ...........
@@ -350,7 +428,7 @@ mod tests {
             .L-------J.
             ...........
         "};
-        assert_eq!(part2(&generator(map)), 19);
+        assert_eq!(generator(map).map(|map| part2(&map)), Ok(19));
 
         let map = indoc! {"
             .F----7F7F7F7F-7....
@@ -364,7 +442,7 @@ mod tests {
             ....FJL-7.||.||||...
             ....L---J.LJ.LJLJ...
         "};
-        assert_eq!(part2(&generator(map)), 8);
+        assert_eq!(generator(map).map(|map| part2(&map)), Ok(8));
 
         let map = indoc! {"
             FF7FSF7F7F7F7F7F---7
@@ -378,7 +456,7 @@ mod tests {
             L.L7LFJ|||||FJL7||LJ
             L7JLJL-JLJLJL--JLJ.L
         "};
-        assert_eq!(part2(&generator(map)), 10);
+        assert_eq!(generator(map).map(|map| part2(&map)), Ok(10));
 
         let map = indoc! {"
             FF7FSF7F7F7F7F7F---7
@@ -392,6 +470,51 @@ mod tests {
             L.L7LFJ|||||FJL7||LJ
             L7JLJL-JLJLJL--JLJ.L
         "};
-        assert_eq!(part2(&generator(map)), 10);
+        assert_eq!(generator(map).map(|map| part2(&map)), Ok(10));
+    }
+
+    #[test]
+    fn generator_errors() {
+        assert_eq!(
+            generator(&EXAMPLE_INPUT_1.replace("L|7||", "L|7|X")).err(),
+            Some(ParseError::UnknownTile {
+                location: Location { line: 3, column: 5 },
+                found: 'X',
+            })
+        );
+        assert_eq!(
+            generator(&EXAMPLE_INPUT_1.replace('S', "-")).err(),
+            Some(ParseError::MissingAnimal {
+                location: Location { line: 6, column: 1 },
+            })
+        );
+        assert_eq!(
+            generator(&EXAMPLE_INPUT_1.replace("L|-JF", "L|-J")).err(),
+            Some(ParseError::RaggedLine {
+                location: Location { line: 5, column: 5 },
+                expected_width: 5,
+                found_width: 4,
+            })
+        );
+        assert_eq!(
+            generator("S-\n").err(),
+            Some(ParseError::UnconnectedAnimal {
+                location: Location { line: 1, column: 1 },
+                connecting_pipes: 1,
+            })
+        );
+        // Both pipes from the `S` lead off the map
+        assert_eq!(
+            generator(".S-\n.|.\n").err(),
+            Some(ParseError::OpenLoop {
+                location: Location { line: 1, column: 2 },
+            })
+        );
+        assert_eq!(
+            generator(&EXAMPLE_INPUT_1.replace("L|7||", "")).err(),
+            Some(ParseError::EmptyLine {
+                location: Location { line: 3, column: 1 },
+            })
+        );
     }
 }
@@ -1,5 +1,9 @@
+use crate::{
+    parse::{self, Line, Location},
+    solution::{variants, Solution, Variant},
+};
 use getset::Getters;
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 #[derive(Getters)]
 pub struct Game {
@@ -8,55 +12,122 @@ pub struct Game {
     reveals: Vec<CubesReveal>,
 }
 
+#[allow(clippy::struct_field_names)] // Reads better than `red`, `green` & `blue` in the game rules
 pub struct CubesReveal {
     red_cubes: u32,
     green_cubes: u32,
     blue_cubes: u32,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    MissingPrefix {
+        location: Location,
+        expected: &'static str,
+    },
+    MissingSeparator {
+        location: Location,
+        expected: char,
+    },
+    InvalidNumber {
+        location: Location,
+        found: String,
+    },
+    UnknownColor {
+        location: Location,
+        found: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingPrefix { location, expected } => {
+                write!(
+                    f,
+                    "{location}: expected the line to start with `{expected}`"
+                )
+            }
+            ParseError::MissingSeparator { location, expected } => {
+                write!(f, "{location}: expected `{expected}`")
+            }
+            ParseError::InvalidNumber { location, found } => {
+                write!(f, "{location}: expected a number, found `{found}`")
+            }
+            ParseError::UnknownColor { location, found } => {
+                write!(
+                    f,
+                    "{location}: expected red, green or blue, found `{found}`"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn parse_number(line: &Line, num_str: &str) -> Result<u32, ParseError> {
+    num_str.parse().map_err(|_| ParseError::InvalidNumber {
+        location: line.locate(num_str),
+        found: num_str.to_string(),
+    })
+}
+
 impl CubesReveal {
-    fn from_reveal_string(reveal_string: &str) -> Self {
+    fn from_reveal_string(line: &Line, reveal_string: &str) -> Result<Self, ParseError> {
         let cube_counts = reveal_string
             .trim()
             .split(',')
             .map(|cube_reveal| {
-                let (count, color) = cube_reveal
-                    .trim()
-                    .split_once(' ')
-                    .expect("Cube reveal should be space separated");
-                let count = count
-                    .parse()
-                    .expect("Count in cube reveal should be a number");
-                (color, count)
+                let cube_reveal = cube_reveal.trim();
+                let (count, color) =
+                    cube_reveal
+                        .split_once(' ')
+                        .ok_or_else(|| ParseError::MissingSeparator {
+                            location: line.locate(cube_reveal),
+                            expected: ' ',
+                        })?;
+                if !matches!(color, "red" | "green" | "blue") {
+                    return Err(ParseError::UnknownColor {
+                        location: line.locate(color),
+                        found: color.to_string(),
+                    });
+                }
+                Ok((color, parse_number(line, count)?))
             })
-            .collect::<HashMap<&str, u32>>();
-        Self {
+            .collect::<Result<HashMap<&str, u32>, _>>()?;
+        Ok(Self {
             red_cubes: *cube_counts.get("red").unwrap_or(&0),
             green_cubes: *cube_counts.get("green").unwrap_or(&0),
             blue_cubes: *cube_counts.get("blue").unwrap_or(&0),
-        }
+        })
     }
 }
 
-pub fn generator(input: &str) -> Vec<Game> {
-    input
-        .lines()
-        .map(|game_record| {
-            let (game_id, game_reveals) = game_record
-                .split_once(':')
-                .expect("Record of game should be in the expected format");
+pub fn generator(input: &str) -> Result<Vec<Game>, ParseError> {
+    parse::lines(input)
+        .map(|line| {
+            let (game_id, game_reveals) =
+                line.text
+                    .split_once(':')
+                    .ok_or_else(|| ParseError::MissingSeparator {
+                        location: line.end(),
+                        expected: ':',
+                    })?;
             let id = game_id
                 .strip_prefix("Game ")
-                .expect("Record of game should start with `Game `")
-                .trim()
-                .parse::<u32>()
-                .expect("Record of game should have an ID number");
-            let reveals: Vec<CubesReveal> = game_reveals
+                .ok_or_else(|| ParseError::MissingPrefix {
+                    location: line.start(),
+                    expected: "Game ",
+                })?
+                .trim();
+            let id = parse_number(&line, id)?;
+            let reveals = game_reveals
                 .trim()
                 .split(';')
-                .map(|cubes_reveal| CubesReveal::from_reveal_string(cubes_reveal.trim()))
-                .collect();
-            Game { id, reveals }
+                .map(|cubes_reveal| CubesReveal::from_reveal_string(&line, cubes_reveal.trim()))
+                .collect::<Result<Vec<CubesReveal>, _>>()?;
+            Ok(Game { id, reveals })
         })
         .collect()
 }
@@ -65,6 +136,7 @@ pub fn part1(games: &[Game]) -> u32 {
     games.iter().filter(is_game_possible).map(Game::id).sum()
 }
 
+#[allow(clippy::trivially_copy_pass_by_ref)] // && required to allow use as function reference
 fn is_game_possible(game: &&Game) -> bool {
     game.reveals
         .iter()
@@ -97,6 +169,20 @@ fn power_of_minimum_set(game: &Game) -> u32 {
     max_red_cubes_revealed * max_green_cubes_revealed * max_blue_cubes_revealed
 }
 
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 2;
+    type Parsed = Vec<Game>;
+    type Answer = u32;
+    type Error = ParseError;
+    const PARTS: [&'static [Variant<Self>]; 2] = [variants![part1], variants![part2]];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error> {
+        generator(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,7 +197,7 @@ mod tests {
             Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
             Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
         "};
-        assert_eq!(part1(&generator(input)), 8);
+        assert_eq!(generator(input).map(|games| part1(&games)), Ok(8));
     }
 
     #[test]
@@ -123,6 +209,40 @@ mod tests {
             Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
             Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
         "};
-        assert_eq!(part2(&generator(input)), 2286);
+        assert_eq!(generator(input).map(|games| part2(&games)), Ok(2286));
+    }
+
+    #[test]
+    fn generator_errors() {
+        assert_eq!(
+            generator("Game 1: 3 blue; 4 red\nGame 2: 1 blue, 2 purple").err(),
+            Some(ParseError::UnknownColor {
+                location: Location {
+                    line: 2,
+                    column: 19
+                },
+                found: "purple".to_string(),
+            })
+        );
+        assert_eq!(
+            generator("Game 1: 3 blue; x red").err(),
+            Some(ParseError::InvalidNumber {
+                location: Location {
+                    line: 1,
+                    column: 17
+                },
+                found: "x".to_string(),
+            })
+        );
+        assert_eq!(
+            generator("Game 1 3 blue").err(),
+            Some(ParseError::MissingSeparator {
+                location: Location {
+                    line: 1,
+                    column: 14
+                },
+                expected: ':',
+            })
+        );
     }
 }
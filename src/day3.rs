@@ -1,92 +1,121 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fmt,
+use crate::{
+    grid::{Direction, Grid, GridError, Position},
+    parse::{self, Location},
+    solution::{variants, Solution, Variant},
 };
+use std::{collections::HashSet, convert::Infallible, fmt};
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-struct Coordinate(u32, u32);
 pub struct EngineSchematic {
-    max_coords: Coordinate,
-    items: HashMap<Coordinate, SchematicItem>,
+    items: Grid<SchematicItem>,
 }
 
 impl EngineSchematic {
-    fn get_item(&self, x: u32, y: u32) -> Option<&SchematicItem> {
-        self.items.get(&Coordinate(x, y))
-    }
-    fn is_digit(&self, coordinate: &Coordinate) -> bool {
-        self.items
-            .get(coordinate)
-            .filter(|item| matches!(item, SchematicItem::Digit(_)))
-            .is_some()
+    fn get_digit(&self, position: Position) -> Option<u32> {
+        match self.items.get(position) {
+            Some(SchematicItem::Digit(digit)) => Some(*digit),
+            _ => None,
+        }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SchematicItem {
+    Blank,
     Symbol(char),
     Digit(u32),
 }
 
+impl SchematicItem {
+    fn from_char(c: char) -> Self {
+        match c {
+            '.' => Self::Blank,
+            other => other.to_digit(10).map_or(Self::Symbol(other), Self::Digit),
+        }
+    }
+}
+
+impl fmt::Display for SchematicItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchematicItem::Blank => write!(f, "."),
+            SchematicItem::Symbol(sym) => write!(f, "{sym}"),
+            SchematicItem::Digit(num) => write!(f, "{num}"),
+        }
+    }
+}
+
 impl fmt::Display for EngineSchematic {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let Coordinate(max_x, max_y) = self.max_coords;
-        let mut output = String::new();
-        for y in 0..=max_y {
-            for x in 0..=max_x {
-                if let Some(schematic_item) = self.items.get(&Coordinate(x, y)) {
-                    match schematic_item {
-                        SchematicItem::Symbol(sym) => output.push(*sym),
-                        SchematicItem::Digit(num) => output.push_str(num.to_string().as_str()),
-                    }
-                } else {
-                    output.push('.');
-                }
+        write!(f, "{}", self.items)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    EmptySchematic {
+        location: Location,
+    },
+    RaggedLine {
+        location: Location,
+        expected_width: usize,
+        found_width: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::EmptySchematic { location } => {
+                write!(f, "{location}: expected a non-empty line of the schematic")
             }
-            output.push('\n');
+            ParseError::RaggedLine {
+                location,
+                expected_width,
+                found_width,
+            } => write!(
+                f,
+                "{location}: expected {expected_width} characters like the first line, found {found_width}"
+            ),
         }
-        write!(f, "{}", output)
     }
 }
 
-pub fn generator(input: &str) -> EngineSchematic {
-    let mut engine_schematic_items = HashMap::new();
-    let mut x_max = 0;
-    let mut y_max = 0;
-    input.lines().enumerate().for_each(|(y, schematic_line)| {
-        schematic_line
-            .chars()
-            .enumerate()
-            .for_each(|(x, c)| match c {
-                '.' => (),
-                '0'..='9' => {
-                    engine_schematic_items.insert(
-                        Coordinate(x as u32, y as u32),
-                        SchematicItem::Digit(c.to_digit(10).unwrap()),
-                    );
-                }
-                symbol => {
-                    engine_schematic_items.insert(
-                        Coordinate(x as u32, y as u32),
-                        SchematicItem::Symbol(symbol),
-                    );
-                }
-            });
-        x_max = schematic_line.len() - 1;
-        y_max = y;
-    });
-    EngineSchematic {
-        max_coords: Coordinate(x_max as u32, y_max as u32),
-        items: engine_schematic_items,
+impl std::error::Error for ParseError {}
+
+impl From<GridError<Infallible>> for ParseError {
+    fn from(error: GridError<Infallible>) -> Self {
+        match error {
+            GridError::EmptyLine { location } => ParseError::EmptySchematic { location },
+            GridError::RaggedLine {
+                location,
+                expected_width,
+                found_width,
+            } => ParseError::RaggedLine {
+                location,
+                expected_width,
+                found_width,
+            },
+            GridError::Cell(never) => match never {},
+        }
     }
 }
 
+pub fn generator(input: &str) -> Result<EngineSchematic, ParseError> {
+    let items = Grid::parse(input, |c, _| Ok(SchematicItem::from_char(c)))?;
+    if items.is_empty() {
+        return Err(ParseError::EmptySchematic {
+            location: parse::end_of_input(input),
+        });
+    }
+    Ok(EngineSchematic { items })
+}
+
 pub fn part1(engine_schematic: &EngineSchematic) -> u32 {
     engine_schematic
         .items
         .iter()
         .filter(|(_, item)| matches!(item, SchematicItem::Symbol(_)))
-        .flat_map(|(coordinate, _)| get_adjacent_numbers(coordinate, engine_schematic))
+        .flat_map(|(position, _)| get_adjacent_numbers(position, engine_schematic))
         .sum()
 }
 
@@ -95,7 +124,7 @@ pub fn part2(engine_schematic: &EngineSchematic) -> u32 {
         .items
         .iter()
         .filter(|(_, item)| matches!(item, SchematicItem::Symbol('*')))
-        .map(|(coordinate, _)| get_adjacent_numbers(coordinate, engine_schematic))
+        .map(|(position, _)| get_adjacent_numbers(position, engine_schematic))
         .filter_map(|nums| {
             if nums.len() == 2 {
                 Some(nums[0] * nums[1])
@@ -106,78 +135,51 @@ pub fn part2(engine_schematic: &EngineSchematic) -> u32 {
         .sum()
 }
 
-fn get_adjacent_numbers(coordinate: &Coordinate, engine_schematic: &EngineSchematic) -> Vec<u32> {
-    let adjacent_coordinates = get_adjacent_coordinates(coordinate, &engine_schematic.max_coords);
-    let digit_coordinates = adjacent_coordinates
-        .iter()
-        .filter(|coord| engine_schematic.is_digit(coord))
-        .collect();
-    extract_numbers(digit_coordinates, engine_schematic)
+fn get_adjacent_numbers(position: Position, engine_schematic: &EngineSchematic) -> Vec<u32> {
+    // A number can touch the symbol with several of its digits, so it's identified by where it
+    // starts to only be counted once
+    let number_starts = engine_schematic
+        .items
+        .neighbours8(position)
+        .filter(|neighbour| engine_schematic.get_digit(*neighbour).is_some())
+        .map(|digit_position| find_number_start(digit_position, engine_schematic))
+        .collect::<HashSet<_>>();
+    number_starts
+        .into_iter()
+        .map(|start| read_number(start, engine_schematic))
+        .collect()
 }
 
-fn extract_numbers(
-    digit_coordinates: Vec<&Coordinate>,
-    engine_schematic: &EngineSchematic,
-) -> Vec<u32> {
-    let mut visited = HashSet::new();
-    let mut nums = Vec::new();
-    for Coordinate(cx, cy) in digit_coordinates {
-        if visited.contains(&(*cx, *cy)) {
-            continue;
+fn find_number_start(mut position: Position, engine_schematic: &EngineSchematic) -> Position {
+    while let Some(left) = engine_schematic.items.step(position, Direction::Left) {
+        if engine_schematic.get_digit(left).is_none() {
+            break;
         }
-        visited.insert((*cx, *cy));
-        let mut num_str = String::new();
-        // Collect digits on left
-        let mut x = *cx;
-        while let Some(SchematicItem::Digit(d)) = engine_schematic.get_item(x, *cy) {
-            visited.insert((x.to_owned(), *cy));
-            num_str = format!("{}{}", d, num_str);
-            if x > 0 {
-                x -= 1;
-            } else {
-                break;
-            }
-        }
-        // Collect digits on the right
-        let max_x = engine_schematic.max_coords.0;
-        let mut x = *cx + 1; // could overflow but won't matter, at least yet, so keeping symmetry
-        while let Some(SchematicItem::Digit(d)) = engine_schematic.get_item(x, *cy) {
-            visited.insert((x.to_owned(), *cy));
-            num_str = format!("{}{}", num_str, d);
-            if x < max_x {
-                x += 1;
-            } else {
-                break;
-            }
-        }
-        nums.push(
-            num_str
-                .parse()
-                .expect("Digits appended should form a number"),
-        );
+        position = left;
     }
-    nums
+    position
 }
 
-fn get_adjacent_coordinates(coordinate: &Coordinate, max_coords: &Coordinate) -> Vec<Coordinate> {
-    let Coordinate(x, y) = coordinate;
-    let Coordinate(max_x, max_y) = max_coords;
-    let x_possibilities = match *x {
-        0 => vec![0, 1],
-        x if x == *max_x => vec![max_x - 1, *max_x],
-        other => vec![other - 1, other, other + 1],
-    };
-    let y_possibilities = match *y {
-        0 => vec![0, 1],
-        y if y == *max_y => vec![max_y - 1, *max_y],
-        other => vec![other - 1, other, other + 1],
-    };
-    x_possibilities
-        .into_iter()
-        .flat_map(|x| y_possibilities.iter().map(move |y| Coordinate(x, *y)))
-        // filter out num's own overlapping coordinates
-        .filter(|Coordinate(px, py)| !(py == y && px == x))
-        .collect()
+fn read_number(start: Position, engine_schematic: &EngineSchematic) -> u32 {
+    std::iter::successors(Some(start), |position| {
+        engine_schematic.items.step(*position, Direction::Right)
+    })
+    .map_while(|position| engine_schematic.get_digit(position))
+    .fold(0, |number, digit| number * 10 + digit)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 3;
+    type Parsed = EngineSchematic;
+    type Answer = u32;
+    type Error = ParseError;
+    const PARTS: [&'static [Variant<Self>]; 2] = [variants![part1], variants![part2]];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error> {
+        generator(input)
+    }
 }
 
 #[cfg(test)]
@@ -200,45 +202,48 @@ mod tests {
             ...$.*....
             .664.598..
         "};
-        let mut expected_items = HashMap::new();
-        expected_items.insert(Coordinate(0, 0), SchematicItem::Digit(4));
-        expected_items.insert(Coordinate(1, 0), SchematicItem::Digit(6));
-        expected_items.insert(Coordinate(2, 0), SchematicItem::Digit(7));
-        expected_items.insert(Coordinate(5, 0), SchematicItem::Digit(1));
-        expected_items.insert(Coordinate(6, 0), SchematicItem::Digit(1));
-        expected_items.insert(Coordinate(7, 0), SchematicItem::Digit(4));
-        expected_items.insert(Coordinate(3, 1), SchematicItem::Symbol('*'));
-        expected_items.insert(Coordinate(2, 2), SchematicItem::Digit(3));
-        expected_items.insert(Coordinate(3, 2), SchematicItem::Digit(5));
-        expected_items.insert(Coordinate(6, 2), SchematicItem::Digit(6));
-        expected_items.insert(Coordinate(7, 2), SchematicItem::Digit(3));
-        expected_items.insert(Coordinate(8, 2), SchematicItem::Digit(3));
-        expected_items.insert(Coordinate(6, 3), SchematicItem::Symbol('#'));
-        expected_items.insert(Coordinate(0, 4), SchematicItem::Digit(6));
-        expected_items.insert(Coordinate(1, 4), SchematicItem::Digit(1));
-        expected_items.insert(Coordinate(2, 4), SchematicItem::Digit(7));
-        expected_items.insert(Coordinate(3, 4), SchematicItem::Symbol('*'));
-        expected_items.insert(Coordinate(5, 5), SchematicItem::Symbol('+'));
-        expected_items.insert(Coordinate(7, 5), SchematicItem::Digit(5));
-        expected_items.insert(Coordinate(8, 5), SchematicItem::Digit(8));
-        expected_items.insert(Coordinate(2, 6), SchematicItem::Digit(5));
-        expected_items.insert(Coordinate(3, 6), SchematicItem::Digit(9));
-        expected_items.insert(Coordinate(4, 6), SchematicItem::Digit(2));
-        expected_items.insert(Coordinate(6, 7), SchematicItem::Digit(7));
-        expected_items.insert(Coordinate(7, 7), SchematicItem::Digit(5));
-        expected_items.insert(Coordinate(8, 7), SchematicItem::Digit(5));
-        expected_items.insert(Coordinate(3, 8), SchematicItem::Symbol('$'));
-        expected_items.insert(Coordinate(5, 8), SchematicItem::Symbol('*'));
-        expected_items.insert(Coordinate(1, 9), SchematicItem::Digit(6));
-        expected_items.insert(Coordinate(2, 9), SchematicItem::Digit(6));
-        expected_items.insert(Coordinate(3, 9), SchematicItem::Digit(4));
-        expected_items.insert(Coordinate(5, 9), SchematicItem::Digit(5));
-        expected_items.insert(Coordinate(6, 9), SchematicItem::Digit(9));
-        expected_items.insert(Coordinate(7, 9), SchematicItem::Digit(8));
-        let schematic = generator(input);
-        println!("{}", schematic);
+        let mut expected_items = Grid::new(10, 10, SchematicItem::Blank);
+        expected_items[Position::new(0, 0)] = SchematicItem::Digit(4);
+        expected_items[Position::new(1, 0)] = SchematicItem::Digit(6);
+        expected_items[Position::new(2, 0)] = SchematicItem::Digit(7);
+        expected_items[Position::new(5, 0)] = SchematicItem::Digit(1);
+        expected_items[Position::new(6, 0)] = SchematicItem::Digit(1);
+        expected_items[Position::new(7, 0)] = SchematicItem::Digit(4);
+        expected_items[Position::new(3, 1)] = SchematicItem::Symbol('*');
+        expected_items[Position::new(2, 2)] = SchematicItem::Digit(3);
+        expected_items[Position::new(3, 2)] = SchematicItem::Digit(5);
+        expected_items[Position::new(6, 2)] = SchematicItem::Digit(6);
+        expected_items[Position::new(7, 2)] = SchematicItem::Digit(3);
+        expected_items[Position::new(8, 2)] = SchematicItem::Digit(3);
+        expected_items[Position::new(6, 3)] = SchematicItem::Symbol('#');
+        expected_items[Position::new(0, 4)] = SchematicItem::Digit(6);
+        expected_items[Position::new(1, 4)] = SchematicItem::Digit(1);
+        expected_items[Position::new(2, 4)] = SchematicItem::Digit(7);
+        expected_items[Position::new(3, 4)] = SchematicItem::Symbol('*');
+        expected_items[Position::new(5, 5)] = SchematicItem::Symbol('+');
+        expected_items[Position::new(7, 5)] = SchematicItem::Digit(5);
+        expected_items[Position::new(8, 5)] = SchematicItem::Digit(8);
+        expected_items[Position::new(2, 6)] = SchematicItem::Digit(5);
+        expected_items[Position::new(3, 6)] = SchematicItem::Digit(9);
+        expected_items[Position::new(4, 6)] = SchematicItem::Digit(2);
+        expected_items[Position::new(6, 7)] = SchematicItem::Digit(7);
+        expected_items[Position::new(7, 7)] = SchematicItem::Digit(5);
+        expected_items[Position::new(8, 7)] = SchematicItem::Digit(5);
+        expected_items[Position::new(3, 8)] = SchematicItem::Symbol('$');
+        expected_items[Position::new(5, 8)] = SchematicItem::Symbol('*');
+        expected_items[Position::new(1, 9)] = SchematicItem::Digit(6);
+        expected_items[Position::new(2, 9)] = SchematicItem::Digit(6);
+        expected_items[Position::new(3, 9)] = SchematicItem::Digit(4);
+        expected_items[Position::new(5, 9)] = SchematicItem::Digit(5);
+        expected_items[Position::new(6, 9)] = SchematicItem::Digit(9);
+        expected_items[Position::new(7, 9)] = SchematicItem::Digit(8);
+        let schematic = generator(input).expect("example should be a valid schematic");
+        println!("{schematic}");
         assert_eq!(schematic.items, expected_items);
-        assert_eq!(schematic.max_coords, Coordinate(9, 9));
+        assert_eq!(
+            (schematic.items.width(), schematic.items.height()),
+            (10, 10)
+        );
     }
 
     #[test]
@@ -255,8 +260,8 @@ mod tests {
             ...$.*....
             .664.598..
         "};
-        let schematic = generator(input);
-        println!("{}", schematic);
+        let schematic = generator(input).expect("example should be a valid schematic");
+        println!("{schematic}");
         assert_eq!(part1(&schematic), 4361);
     }
 
@@ -267,20 +272,20 @@ mod tests {
             ....#.
             23$..1
         "};
-        let mut expected_items = HashMap::new();
-        expected_items.insert(Coordinate(0, 0), SchematicItem::Digit(4));
-        expected_items.insert(Coordinate(3, 0), SchematicItem::Digit(1));
-        expected_items.insert(Coordinate(4, 0), SchematicItem::Digit(3));
-        expected_items.insert(Coordinate(5, 0), SchematicItem::Digit(4));
-        expected_items.insert(Coordinate(4, 1), SchematicItem::Symbol('#'));
-        expected_items.insert(Coordinate(0, 2), SchematicItem::Digit(2));
-        expected_items.insert(Coordinate(1, 2), SchematicItem::Digit(3));
-        expected_items.insert(Coordinate(2, 2), SchematicItem::Symbol('$'));
-        expected_items.insert(Coordinate(5, 2), SchematicItem::Digit(1));
-        let schematic = generator(input);
-        println!("{}", schematic);
+        let mut expected_items = Grid::new(6, 3, SchematicItem::Blank);
+        expected_items[Position::new(0, 0)] = SchematicItem::Digit(4);
+        expected_items[Position::new(3, 0)] = SchematicItem::Digit(1);
+        expected_items[Position::new(4, 0)] = SchematicItem::Digit(3);
+        expected_items[Position::new(5, 0)] = SchematicItem::Digit(4);
+        expected_items[Position::new(4, 1)] = SchematicItem::Symbol('#');
+        expected_items[Position::new(0, 2)] = SchematicItem::Digit(2);
+        expected_items[Position::new(1, 2)] = SchematicItem::Digit(3);
+        expected_items[Position::new(2, 2)] = SchematicItem::Symbol('$');
+        expected_items[Position::new(5, 2)] = SchematicItem::Digit(1);
+        let schematic = generator(input).expect("example should be a valid schematic");
+        println!("{schematic}");
         assert_eq!(schematic.items, expected_items);
-        assert_eq!(schematic.max_coords, Coordinate(5, 2));
+        assert_eq!((schematic.items.width(), schematic.items.height()), (6, 3));
     }
 
     #[test]
@@ -289,7 +294,10 @@ mod tests {
             3..21
             ...*1
         "};
-        assert_eq!(part1(&generator(input)), 21 + 1);
+        assert_eq!(
+            generator(input).map(|schematic| part1(&schematic)),
+            Ok(21 + 1)
+        );
     }
 
     #[test]
@@ -298,7 +306,32 @@ mod tests {
             3..20
             ...*1
         "};
-        assert_eq!(part1(&generator(input)), 20 + 1);
+        assert_eq!(
+            generator(input).map(|schematic| part1(&schematic)),
+            Ok(20 + 1)
+        );
+    }
+
+    #[test]
+    fn generator_errors() {
+        let input = indoc! {"
+            3..21
+            ...*
+        "};
+        assert_eq!(
+            generator(input).err(),
+            Some(ParseError::RaggedLine {
+                location: Location { line: 2, column: 5 },
+                expected_width: 5,
+                found_width: 4,
+            })
+        );
+        assert_eq!(
+            generator("").err(),
+            Some(ParseError::EmptySchematic {
+                location: Location { line: 1, column: 1 },
+            })
+        );
     }
 
     #[test]
@@ -306,16 +339,16 @@ mod tests {
         let input = indoc! {"
             4..134
             ....#."};
-        let mut expected_items = HashMap::new();
-        expected_items.insert(Coordinate(0, 0), SchematicItem::Digit(4));
-        expected_items.insert(Coordinate(3, 0), SchematicItem::Digit(1));
-        expected_items.insert(Coordinate(4, 0), SchematicItem::Digit(3));
-        expected_items.insert(Coordinate(5, 0), SchematicItem::Digit(4));
-        expected_items.insert(Coordinate(4, 1), SchematicItem::Symbol('#'));
-        let schematic = generator(input);
-        println!("{}", schematic);
+        let mut expected_items = Grid::new(6, 2, SchematicItem::Blank);
+        expected_items[Position::new(0, 0)] = SchematicItem::Digit(4);
+        expected_items[Position::new(3, 0)] = SchematicItem::Digit(1);
+        expected_items[Position::new(4, 0)] = SchematicItem::Digit(3);
+        expected_items[Position::new(5, 0)] = SchematicItem::Digit(4);
+        expected_items[Position::new(4, 1)] = SchematicItem::Symbol('#');
+        let schematic = generator(input).expect("example should be a valid schematic");
+        println!("{schematic}");
         assert_eq!(schematic.items, expected_items);
-        assert_eq!(schematic.max_coords, Coordinate(5, 1));
+        assert_eq!((schematic.items.width(), schematic.items.height()), (6, 2));
     }
 
     #[test]
@@ -332,8 +365,8 @@ mod tests {
             ...$.*....
             .664.598..
         "};
-        let schematic = generator(input);
-        println!("{}", schematic);
-        assert_eq!(part2(&schematic), 467835);
+        let schematic = generator(input).expect("example should be a valid schematic");
+        println!("{schematic}");
+        assert_eq!(part2(&schematic), 467_835);
     }
 }
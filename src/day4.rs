@@ -1,6 +1,13 @@
-use std::collections::{HashMap, HashSet};
+use crate::{
+    parse::{self, Line, Location},
+    solution::{variants, Solution, Variant},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
-type Pile = HashMap<u32, Card>;
+pub type Pile = HashMap<u32, Card>;
 
 pub struct Card {
     winning_numbers: HashSet<u32>,
@@ -16,30 +23,102 @@ impl Card {
     }
 }
 
-pub fn generator(input: &str) -> Pile {
-    let expectation = "Input should be in prescribed format";
-    input
-        .lines()
-        .map(|line| {
-            let (card_string, numbers) = line.split_once(':').expect(expectation);
-            let (_, card_num) = card_string.split_once(' ').expect(expectation);
-            let card_num = card_num.trim().parse().expect(expectation);
-            let (winning_numbers, player_numbers) = numbers.split_once('|').expect(expectation);
-            let winning_numbers = winning_numbers
-                .split_whitespace()
-                .map(|num| num.parse().expect(expectation))
-                .collect();
-            let player_numbers = player_numbers
-                .split_whitespace()
-                .map(|num| num.parse().expect(expectation))
-                .collect();
-            (
-                card_num,
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    MissingSeparator {
+        location: Location,
+        expected: char,
+    },
+    InvalidNumber {
+        location: Location,
+        found: String,
+    },
+    UnexpectedCardNumber {
+        location: Location,
+        expected: u32,
+        found: u32,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingSeparator { location, expected } => {
+                write!(f, "{location}: expected `{expected}`")
+            }
+            ParseError::InvalidNumber { location, found } => {
+                write!(f, "{location}: expected a number, found `{found}`")
+            }
+            ParseError::UnexpectedCardNumber {
+                location,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{location}: expected card {expected}, found card {found}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn parse_number(line: &Line, num_str: &str) -> Result<u32, ParseError> {
+    num_str.parse().map_err(|_| ParseError::InvalidNumber {
+        location: line.locate(num_str),
+        found: num_str.to_string(),
+    })
+}
+
+fn parse_numbers(line: &Line, numbers: &str) -> Result<HashSet<u32>, ParseError> {
+    numbers
+        .split_whitespace()
+        .map(|num| parse_number(line, num))
+        .collect()
+}
+
+/// Cards are expected to be numbered 1, 2, 3... in order, as part 2 relies on it
+pub fn generator(input: &str) -> Result<Pile, ParseError> {
+    parse::lines(input)
+        .zip(1..)
+        .map(|(line, expected_card_num)| {
+            let (card_string, numbers) =
+                line.text
+                    .split_once(':')
+                    .ok_or_else(|| ParseError::MissingSeparator {
+                        location: line.end(),
+                        expected: ':',
+                    })?;
+            let (_, card_num) =
+                card_string
+                    .split_once(' ')
+                    .ok_or_else(|| ParseError::MissingSeparator {
+                        location: line.locate(numbers),
+                        expected: ' ',
+                    })?;
+            let card_num = card_num.trim();
+            let found_card_num = parse_number(&line, card_num)?;
+            if found_card_num != expected_card_num {
+                return Err(ParseError::UnexpectedCardNumber {
+                    location: line.locate(card_num),
+                    expected: expected_card_num,
+                    found: found_card_num,
+                });
+            }
+            let (winning_numbers, player_numbers) =
+                numbers
+                    .split_once('|')
+                    .ok_or_else(|| ParseError::MissingSeparator {
+                        location: line.end(),
+                        expected: '|',
+                    })?;
+            Ok((
+                found_card_num,
                 Card {
-                    winning_numbers,
-                    player_numbers,
+                    winning_numbers: parse_numbers(&line, winning_numbers)?,
+                    player_numbers: parse_numbers(&line, player_numbers)?,
                 },
-            )
+            ))
         })
         .collect()
 }
@@ -63,9 +142,8 @@ pub fn part2(pile: &Pile) -> u32 {
         new_pile_counts.insert(i, 1);
     }
     for i in 1..=pile.len() as u32 {
-        let card = pile.get(&i).unwrap();
-        let won_numbers_count = card.get_won_numbers_count();
-        let self_count = new_pile_counts.get(&i).unwrap().to_owned();
+        let won_numbers_count = pile[&i].get_won_numbers_count();
+        let self_count = new_pile_counts[&i];
         for j in 1..=won_numbers_count {
             new_pile_counts
                 .entry(i + j)
@@ -76,6 +154,20 @@ pub fn part2(pile: &Pile) -> u32 {
     new_pile_counts.values().sum()
 }
 
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 4;
+    type Parsed = Pile;
+    type Answer = u32;
+    type Error = ParseError;
+    const PARTS: [&'static [Variant<Self>]; 2] = [variants![part1], variants![part2]];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error> {
+        generator(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -91,7 +183,7 @@ mod test {
             Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
             Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
         "};
-        assert_eq!(part1(&generator(input)), 13);
+        assert_eq!(generator(input).map(|pile| part1(&pile)), Ok(13));
     }
 
     #[test]
@@ -104,6 +196,32 @@ mod test {
             Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
             Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
         "};
-        assert_eq!(part2(&generator(input)), 30);
+        assert_eq!(generator(input).map(|pile| part2(&pile)), Ok(30));
+    }
+
+    #[test]
+    fn generator_errors() {
+        let input = indoc! {"
+            Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+            Card 3: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+        "};
+        assert_eq!(
+            generator(input).err(),
+            Some(ParseError::UnexpectedCardNumber {
+                location: Location { line: 2, column: 6 },
+                expected: 2,
+                found: 3,
+            })
+        );
+        assert_eq!(
+            generator("Card 1: 41 48 83 86 17 83 86  6 31 17").err(),
+            Some(ParseError::MissingSeparator {
+                location: Location {
+                    line: 1,
+                    column: 38
+                },
+                expected: '|',
+            })
+        );
     }
 }
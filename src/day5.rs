@@ -1,37 +1,202 @@
-use std::ops::RangeInclusive;
+use crate::{
+    parse::{self, Line, Location},
+    range_set::RangeSet,
+    solution::{variants, Solution, Variant},
+};
+use getset::CopyGetters;
+use serde::{Deserialize, Serialize};
+use std::{fmt, ops::RangeInclusive};
 
-type Seeds = Vec<u32>;
-type Mapping = Vec<Vec<u32>>;
+pub mod brute_force;
+pub mod diagram;
 
+pub type Seeds = Vec<u64>;
+/// Range mappings in the order they're listed, the first one moving a number taking precedence
+pub type Mapping = Vec<RangeMapping>;
+
+/// Moves the `length` numbers from `source` to the ones from `destination`, as read from a
+/// `destination source length` line of a map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CopyGetters)]
+pub struct RangeMapping {
+    #[getset(get_copy = "pub")]
+    destination: u64,
+    #[getset(get_copy = "pub")]
+    source: u64,
+    #[getset(get_copy = "pub")]
+    length: u64,
+}
+
+impl RangeMapping {
+    /// Range mapping of `length` numbers, unless its source or destination range would go past
+    /// `u64::MAX`
+    pub fn new(destination: u64, source: u64, length: u64) -> Result<Self, OverflowError> {
+        for start in [destination, source] {
+            if length
+                .checked_sub(1)
+                .is_some_and(|last_offset| start.checked_add(last_offset).is_none())
+            {
+                return Err(OverflowError { start, length });
+            }
+        }
+        Ok(Self {
+            destination,
+            source,
+            length,
+        })
+    }
+
+    /// Numbers moved, or `None` if the length is 0
+    pub fn source_range(&self) -> Option<RangeInclusive<u64>> {
+        let last_offset = self.length.checked_sub(1)?;
+        Some(self.source..=self.source + last_offset)
+    }
+
+    /// Numbers moved to, or `None` if the length is 0
+    pub fn destination_range(&self) -> Option<RangeInclusive<u64>> {
+        let last_offset = self.length.checked_sub(1)?;
+        Some(self.destination..=self.destination + last_offset)
+    }
+
+    /// How far each number is moved
+    pub fn offset(&self) -> i128 {
+        i128::from(self.destination) - i128::from(self.source)
+    }
+
+    /// Where `number` is moved to, if it's in the source range
+    pub fn apply(&self, number: u64) -> Option<u64> {
+        let offset = number
+            .checked_sub(self.source)
+            .filter(|offset| *offset < self.length)?;
+        Some(self.destination + offset)
+    }
+
+    /// Where the numbers of `input` that are in the source range are moved to
+    pub fn apply_to_ranges(&self, input: &RangeSet) -> RangeSet {
+        let Some(source) = self.source_range() else {
+            return RangeSet::new();
+        };
+        let moved = |number: u64| self.destination + (number - self.source);
+        input
+            .intersection(&RangeSet::from(source))
+            .ranges()
+            .iter()
+            .map(|range| moved(*range.start())..=moved(*range.end()))
+            .collect()
+    }
+}
+
+/// The `length` numbers from `start` go past `u64::MAX`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError {
+    pub start: u64,
+    pub length: u64,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the {} numbers from {} go past the largest supported number, {}",
+            self.length,
+            self.start,
+            u64::MAX
+        )
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+/// A map from numbers of the `source` category to numbers of the `destination` one, read from a
+/// `source-to-destination map:` section
+#[derive(Debug, PartialEq)]
+pub struct CategoryMap {
+    pub source: String,
+    pub destination: String,
+    pub mapping: Mapping,
+}
+
+/// Maps between categories, forming a graph in which each category has at most one map out of it,
+/// so that there's at most one path from one category to another
 #[derive(Debug, PartialEq)]
 pub struct GardenAlmanac {
-    seed_to_soil: Mapping,
-    soil_to_fertilizer: Mapping,
-    fertilizer_to_water: Mapping,
-    water_to_light: Mapping,
-    light_to_temperature: Mapping,
-    temperature_to_humidity: Mapping,
-    humidity_to_location: Mapping,
+    maps: Vec<CategoryMap>,
+    /// Indices in `maps` of the path from seed to location, which the puzzle is about
+    seed_to_location: Vec<usize>,
 }
 
 impl GardenAlmanac {
-    #[allow(clippy::trivially_copy_pass_by_ref)] // && required to allow use as function reference
-    fn apply_mapping(input: u32, mapping: &&Mapping) -> u32 {
+    /// Almanac with the given maps, which have to lead from seed to location. Only the first map
+    /// out of a category is used.
+    pub fn new(maps: Vec<CategoryMap>) -> Result<Self, PathError> {
+        let mut almanac = Self {
+            maps,
+            seed_to_location: Vec::new(),
+        };
+        almanac.seed_to_location = almanac.path_indices("seed", "location")?;
+        Ok(almanac)
+    }
+
+    pub fn maps(&self) -> &[CategoryMap] {
+        &self.maps
+    }
+
+    fn path_indices(&self, from: &str, to: &str) -> Result<Vec<usize>, PathError> {
+        let mut path = Vec::new();
+        let mut visited = vec![from];
+        let mut category = from;
+        while category != to {
+            let index = self
+                .maps
+                .iter()
+                .position(|map| map.source == category)
+                .ok_or_else(|| PathError::Missing {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    stuck_at: category.to_string(),
+                })?;
+            category = &self.maps[index].destination;
+            if visited.contains(&category) {
+                return Err(PathError::Cyclic {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    category: category.to_string(),
+                });
+            }
+            visited.push(category);
+            path.push(index);
+        }
+        Ok(path)
+    }
+
+    /// The maps to go through, in order, to get from the `from` category to the `to` one. Going
+    /// from a category to itself takes no maps.
+    pub fn path(&self, from: &str, to: &str) -> Result<Vec<&CategoryMap>, PathError> {
+        Ok(self
+            .path_indices(from, to)?
+            .into_iter()
+            .map(|index| &self.maps[index])
+            .collect())
+    }
+
+    /// The maps from the `from` category to the `to` one, compiled into a single function
+    pub fn mapping(&self, from: &str, to: &str) -> Result<CompiledMapping, PathError> {
+        Ok(self
+            .path(from, to)?
+            .into_iter()
+            .fold(CompiledMapping::default(), |compiled, map| {
+                compiled.then(&CompiledMapping::from_mapping(&map.mapping))
+            }))
+    }
+
+    fn apply_mapping(input: u64, mapping: &Mapping) -> u64 {
         mapping
             .iter()
-            .find(|range_mapping| {
-                let input_start = range_mapping[1];
-                let input_range = input_start..input_start + range_mapping[2];
-                input_range.contains(&input)
-            })
-            .map(|matching_range_mapping| {
-                input + matching_range_mapping[0] - matching_range_mapping[1]
-            })
-            // .unwrap_or_else(|| panic!("There should be at least one matching range for {} in {:?}", input, mapping))
+            .find_map(|range_mapping| range_mapping.apply(input))
             .unwrap_or(input)
     }
 
-    /// Takes a range and applies the provided mapping to return a set of output ranges
+    /// Takes a set of ranges and applies the provided mapping to return the set of output ranges,
+    /// with overlapping output ranges coalesced
     ///
     /// A vizualization: (`|` indicates start & end of a range)
     /// ```text
@@ -46,223 +211,798 @@ impl GardenAlmanac {
     ///                    c]                             |+y|
     ///                    d]                    |+|
     ///                    e]                    |+z|
+    /// Output set      ->       |++++++++|      |++|     |++|
     /// ```
+    /// [`diagram::render_mapping`] draws such diagrams for any input & mapping.
+    ///
     /// The above example will be written as follows:
     /// ```rust
+    /// # use rusted_reindeer::{day5::{GardenAlmanac, RangeMapping}, range_set::RangeSet};
+    /// let output = GardenAlmanac::apply_mapping_to_ranges(
+    ///     &RangeSet::from(25..=47), // i
+    ///     &vec![
+    ///         RangeMapping::new(28, 30, 7)?, // x
+    ///         RangeMapping::new(50, 37, 4)?, // y
+    ///         RangeMapping::new(41, 44, 6)?, // z
+    ///     ],
+    /// );
     /// assert_eq!(
-    ///     GardenAlmanac::apply_mapping_to_range(
-    ///         &(25..=47), // i
-    ///         &vec![
-    ///             vec![28, 30, 7], // x
-    ///             vec![50, 37, 4], // y
-    ///             vec![41, 44, 6], // z
-    ///         ]
-    ///     )
-    ///     .iter()
-    ///     .collect::<HashSet<_>>(),
+    ///     output.ranges(),
     ///     [
-    ///         25..=29, // a
-    ///         28..=34, // b
+    ///         25..=34, // a & b
+    ///         41..=44, // d & e
     ///         50..=53, // c
-    ///         41..=43, // d
-    ///         41..=44, // e
     ///     ]
-    ///     .iter()
-    ///     .collect::<HashSet<_>>()
     /// );
+    /// # Ok::<(), rusted_reindeer::day5::OverflowError>(())
     /// ```
-    #[allow(clippy::range_minus_one)] // Using inclusive ranges here makes the code simpler
-    fn apply_mapping_to_range(
-        input_range: &RangeInclusive<u32>,
-        mapping: &Mapping,
-    ) -> Vec<RangeInclusive<u32>> {
-        let mut mapped_input_subranges = Vec::new();
-        let mut output_ranges = Vec::new();
+    pub fn apply_mapping_to_ranges(input: &RangeSet, mapping: &Mapping) -> RangeSet {
+        let mut unmapped = input.clone();
+        let mut output = RangeSet::new();
         for range_mapping in mapping {
-            let source_start = range_mapping[1];
-            let source_range = source_start..=(source_start + range_mapping[2] - 1);
-            if source_range.end() < input_range.start() || source_range.start() > input_range.end()
-            {
+            let Some(source) = range_mapping.source_range() else {
+                continue;
+            };
+            // Numbers already mapped by an earlier range mapping are left to that one
+            output = output.union(&range_mapping.apply_to_ranges(&unmapped));
+            unmapped = unmapped.difference(&RangeSet::from(source));
+        }
+        output.union(&unmapped)
+    }
+
+    /// Reports the problems of each map of `input` & the numbers it leaves as they are, reading
+    /// past the lines that the [`generator`] would reject. Only a malformed seeds line or map header
+    /// is an error.
+    pub fn validate(input: &str) -> Result<Vec<MapReport>, ParseError> {
+        let (_, sections) = read_sections(input)?;
+        Ok(sections.iter().map(Self::validate_section).collect())
+    }
+
+    fn validate_section(section: &Section) -> MapReport {
+        let mut issues = Vec::new();
+        let mut ranges: Vec<(Location, RangeInclusive<u64>, RangeInclusive<u64>)> = Vec::new();
+        for line in &section.range_lines {
+            let range_mapping = match parse_range_line(line) {
+                Ok(range_mapping) => range_mapping,
+                Err(error) => {
+                    issues.push(Issue::Malformed(error));
+                    continue;
+                }
+            };
+            let location = line.start();
+            let (Some(destination), Some(source)) = (
+                range_mapping.destination_range(),
+                range_mapping.source_range(),
+            ) else {
+                issues.push(Issue::ZeroLength { location });
                 continue;
+            };
+            for (first, first_destination, first_source) in &ranges {
+                if let Some(overlap) = overlap(first_source, &source) {
+                    issues.push(Issue::OverlappingSources {
+                        location,
+                        first: *first,
+                        overlap,
+                    });
+                }
+                if let Some(overlap) = overlap(first_destination, &destination) {
+                    issues.push(Issue::OverlappingDestinations {
+                        location,
+                        first: *first,
+                        overlap,
+                    });
+                }
             }
-            let input_subrange_start = u32::max(*input_range.start(), *source_range.start());
-            let input_subrange_end = u32::min(*source_range.end(), *input_range.end());
-            let destination_start = range_mapping[0];
-            let destination_subrange_start =
-                input_subrange_start + destination_start - source_start;
-            let destination_subrange_end = input_subrange_end + destination_start - source_start;
-            let input_subrange = input_subrange_start..=input_subrange_end;
-            let destination_subrange = destination_subrange_start..=destination_subrange_end;
-            mapped_input_subranges.push(input_subrange);
-            output_ranges.push(destination_subrange);
+            ranges.push((location, destination, source));
         }
-        let unmapped_input_ranges =
-            get_not_covered_ranges(input_range, &mut mapped_input_subranges);
-        output_ranges.extend(unmapped_input_ranges);
-        output_ranges
-    }
-
-    fn get_location_for_seed(&self, seed: u32) -> u32 {
-        [
-            &self.seed_to_soil,
-            &self.soil_to_fertilizer,
-            &self.fertilizer_to_water,
-            &self.water_to_light,
-            &self.light_to_temperature,
-            &self.temperature_to_humidity,
-            &self.humidity_to_location,
-        ]
-        .iter()
-        .fold(seed, GardenAlmanac::apply_mapping)
-    }
-
-    fn get_location_ranges_for_seed_range(
-        &self,
-        seed_range: RangeInclusive<u32>,
-    ) -> Vec<RangeInclusive<u32>> {
-        [
-            &self.seed_to_soil,
-            &self.soil_to_fertilizer,
-            &self.fertilizer_to_water,
-            &self.water_to_light,
-            &self.light_to_temperature,
-            &self.temperature_to_humidity,
-            &self.humidity_to_location,
-        ]
-        .iter()
-        .fold(vec![seed_range], |ranges, mapping| {
-            ranges
+        let sources = ranges.into_iter().map(|(_, _, source)| source).collect();
+        MapReport {
+            source: section.source.to_string(),
+            destination: section.destination.to_string(),
+            location: section.header.start(),
+            issues,
+            identity: RangeSet::from(0..=u64::MAX).difference(&sources),
+        }
+    }
+
+    /// The mappings from seed to location, in order
+    fn mappings(&self) -> impl Iterator<Item = &Mapping> {
+        self.seed_to_location
+            .iter()
+            .map(|index| &self.maps[*index].mapping)
+    }
+
+    fn get_location_for_seed(&self, seed: u64) -> u64 {
+        self.mappings().fold(seed, GardenAlmanac::apply_mapping)
+    }
+
+    fn get_location_ranges_for_seed_range(&self, seed_range: RangeInclusive<u64>) -> RangeSet {
+        self.mappings()
+            .fold(RangeSet::from(seed_range), |ranges, mapping| {
+                GardenAlmanac::apply_mapping_to_ranges(&ranges, mapping)
+            })
+    }
+
+    /// The whole chain of mappings from seed to location, compiled into a single function
+    pub fn compile(&self) -> CompiledMapping {
+        self.mappings()
+            .fold(CompiledMapping::default(), |compiled, mapping| {
+                compiled.then(&CompiledMapping::from_mapping(mapping))
+            })
+    }
+
+    /// Every seed that ends up at one of `locations`, optionally only among the seed ranges
+    /// declared on the `seeds:` line
+    pub fn seeds_for_locations(&self, locations: &RangeSet, declared: Option<&Seeds>) -> RangeSet {
+        let seeds = self.compile().preimage(locations);
+        match declared {
+            Some(declared) => seeds.intersection(&seed_ranges(declared).collect()),
+            None => seeds,
+        }
+    }
+}
+
+/// Numbers of the `source` range that all get moved by the same `offset`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Segment {
+    pub source: RangeInclusive<u64>,
+    pub offset: i128,
+}
+
+impl Segment {
+    fn apply(&self, input: u64) -> u64 {
+        u64::try_from(i128::from(input) + self.offset)
+            .expect("segments only map into the u64 range")
+    }
+
+    /// Where the part of this segment that's in `range` maps to, if any of it is
+    fn apply_to_range(&self, range: &RangeInclusive<u64>) -> Option<RangeInclusive<u64>> {
+        let start = *self.source.start().max(range.start());
+        let end = *self.source.end().min(range.end());
+        (start <= end).then(|| self.apply(start)..=self.apply(end))
+    }
+}
+
+/// One or more mappings compiled into a single piecewise-linear function, as segments sorted by
+/// source that cover every `u64` between them, touching segments never sharing an offset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Segment>", into = "Vec<Segment>")]
+pub struct CompiledMapping {
+    segments: Vec<Segment>,
+}
+
+/// The identity function
+impl Default for CompiledMapping {
+    fn default() -> Self {
+        Self {
+            segments: vec![Segment {
+                source: 0..=u64::MAX,
+                offset: 0,
+            }],
+        }
+    }
+}
+
+impl CompiledMapping {
+    /// Compiles a single mapping, where ranges listed first take precedence over later
+    /// overlapping ones like in [`GardenAlmanac::apply_mapping`]
+    pub fn from_mapping(mapping: &Mapping) -> Self {
+        let mut unmapped = RangeSet::from(0..=u64::MAX);
+        let mut segments = Vec::new();
+        for range_mapping in mapping {
+            let Some(source) = range_mapping.source_range().map(RangeSet::from) else {
+                continue;
+            };
+            let offset = range_mapping.offset();
+            segments.extend(
+                unmapped
+                    .intersection(&source)
+                    .ranges()
+                    .iter()
+                    .map(|range| Segment {
+                        source: range.clone(),
+                        offset,
+                    }),
+            );
+            unmapped = unmapped.difference(&source);
+        }
+        segments.extend(unmapped.ranges().iter().map(|range| Segment {
+            source: range.clone(),
+            offset: 0,
+        }));
+        Self::merge(segments)
+    }
+
+    /// Sorts segments that cover every `u64`, merging touching ones with the same offset
+    fn merge(mut segments: Vec<Segment>) -> Self {
+        segments.sort_unstable_by_key(|segment| *segment.source.start());
+        let mut merged: Vec<Segment> = Vec::with_capacity(segments.len());
+        for segment in segments {
+            match merged.last_mut() {
+                Some(last) if last.offset == segment.offset => {
+                    last.source = *last.source.start()..=*segment.source.end();
+                }
+                _ => merged.push(segment),
+            }
+        }
+        Self { segments: merged }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Segments with a source overlapping `range`
+    fn overlapping(&self, range: &RangeInclusive<u64>) -> &[Segment] {
+        let first = self
+            .segments
+            .partition_point(|segment| segment.source.end() < range.start());
+        let end = self
+            .segments
+            .partition_point(|segment| segment.source.start() <= range.end());
+        &self.segments[first..end.max(first)]
+    }
+
+    /// Looks up the segment of `input` by binary search
+    pub fn apply(&self, input: u64) -> u64 {
+        self.overlapping(&(input..=input))[0].apply(input)
+    }
+
+    /// Where the numbers of `input` map to, going through each segment at most once
+    pub fn apply_to_ranges(&self, input: &RangeSet) -> RangeSet {
+        input
+            .ranges()
+            .iter()
+            .flat_map(|range| {
+                self.overlapping(range)
+                    .iter()
+                    .filter_map(|segment| segment.apply_to_range(range))
+            })
+            .collect()
+    }
+
+    /// Every number that maps into `output`
+    ///
+    /// # Panics
+    ///
+    /// Never, as the numbers a segment maps to are all moved back into its own source
+    pub fn preimage(&self, output: &RangeSet) -> RangeSet {
+        self.segments
+            .iter()
+            .flat_map(|segment| {
+                let image =
+                    segment.apply(*segment.source.start())..=segment.apply(*segment.source.end());
+                output
+                    .intersection(&RangeSet::from(image))
+                    .shift(-segment.offset)
+                    .expect("the image of a segment comes from its source")
+                    .ranges()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    /// This function followed by `next`
+    ///
+    /// # Panics
+    ///
+    /// Never, as the numbers a segment maps to are all moved back into its own source
+    #[must_use]
+    pub fn then(&self, next: &Self) -> Self {
+        let segments = self
+            .segments
+            .iter()
+            .flat_map(|segment| {
+                let image =
+                    segment.apply(*segment.source.start())..=segment.apply(*segment.source.end());
+                next.overlapping(&image).iter().map(move |next_segment| {
+                    let start = *image.start().max(next_segment.source.start());
+                    let end = *image.end().min(next_segment.source.end());
+                    let unapply = |number: u64| {
+                        u64::try_from(i128::from(number) - segment.offset)
+                            .expect("the image of a segment comes from its source")
+                    };
+                    Segment {
+                        source: unapply(start)..=unapply(end),
+                        offset: segment.offset + next_segment.offset,
+                    }
+                })
+            })
+            .collect();
+        Self::merge(segments)
+    }
+}
+
+impl From<CompiledMapping> for Vec<Segment> {
+    fn from(compiled: CompiledMapping) -> Self {
+        compiled.segments
+    }
+}
+
+/// Checks that deserialized segments cover every `u64` in order & only map into the `u64` range
+impl TryFrom<Vec<Segment>> for CompiledMapping {
+    type Error = String;
+
+    fn try_from(segments: Vec<Segment>) -> Result<Self, Self::Error> {
+        let mut next_start = Some(0);
+        for segment in &segments {
+            let (start, end) = (*segment.source.start(), *segment.source.end());
+            if next_start != Some(start) || start > end {
+                return Err(format!(
+                    "expected a segment starting at {}, found {start}..={end}",
+                    next_start.map_or("nothing".to_string(), |start| start.to_string())
+                ));
+            }
+            if [start, end]
                 .iter()
-                .flat_map(|range| GardenAlmanac::apply_mapping_to_range(range, mapping))
-                .collect()
-        })
+                .any(|number| u64::try_from(i128::from(*number) + segment.offset).is_err())
+            {
+                return Err(format!(
+                    "{start}..={end} moved by {} goes out of the u64 range",
+                    segment.offset
+                ));
+            }
+            next_start = end.checked_add(1);
+        }
+        if next_start.is_some() {
+            return Err("expected the segments to go up to the largest u64".to_string());
+        }
+        Ok(Self::merge(segments))
     }
 }
 
-/// Takes a range and disjoint subranges to return subranges that were not covered by the subranges
-///
-/// A vizualization: (`|` indicates start & end of a range)
-/// ```text
-/// Input range             ->  |*********************|
-/// Covered subranges       ->  |###|-------|##||#|----
-/// Not covered subranges   ->       |#####|       |##|
-/// ```
-#[allow(clippy::range_minus_one)] // Using inclusive ranges here makes the code simpler
-fn get_not_covered_ranges(
-    range: &RangeInclusive<u32>,
-    covered_subranges: &mut [RangeInclusive<u32>],
-) -> Vec<RangeInclusive<u32>> {
-    // covered_subranges.sort_by(|this, other| this.start().cmp(other.start()));
-    covered_subranges.sort_unstable_by_key(|range| *range.start());
-    let mut not_covered_ranges = Vec::new();
-    let mut iter = covered_subranges.iter();
-    let Some(first_subrange) = iter.next() else {
-        return vec![range.clone()];
-    };
-    if first_subrange.start() > range.start() {
-        not_covered_ranges.push(*range.start()..=(first_subrange.start() - 1));
+/// A table of the segments, one per line
+impl fmt::Display for CompiledMapping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for segment in &self.segments {
+            writeln!(
+                f,
+                "{}..={} {:+}",
+                segment.source.start(),
+                segment.source.end(),
+                segment.offset
+            )?;
+        }
+        Ok(())
     }
-    let mut previous_subrange = first_subrange;
-    for cur_subrange in iter.as_ref() {
-        if *cur_subrange.start() > (previous_subrange.end() + 1) {
-            not_covered_ranges.push((previous_subrange.end() + 1)..=(cur_subrange.start() - 1));
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    MissingPrefix {
+        location: Location,
+        expected: &'static str,
+    },
+    MissingMapHeader {
+        location: Location,
+    },
+    InvalidNumber {
+        location: Location,
+        found: String,
+    },
+    /// No pair of numbers on the `seeds:` line makes a non-empty range, so neither part has seeds
+    MissingSeeds {
+        location: Location,
+    },
+    WrongNumberCount {
+        location: Location,
+        found: usize,
+    },
+    RangeOverflow {
+        location: Location,
+        start: u64,
+        length: u64,
+    },
+    DuplicateMap {
+        location: Location,
+        source: String,
+        first: Location,
+    },
+    /// The maps don't lead from seed to location
+    Path(PathError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingPrefix { location, expected } => {
+                write!(f, "{location}: expected the line to start with `{expected}`")
+            }
+            ParseError::MissingMapHeader { location } => {
+                write!(f, "{location}: expected a map header like `seed-to-soil map:`")
+            }
+            ParseError::InvalidNumber { location, found } => {
+                write!(f, "{location}: expected a number, found `{found}`")
+            }
+            ParseError::MissingSeeds { location } => write!(
+                f,
+                "{location}: expected at least one seed range, as a start & a non-zero length"
+            ),
+            ParseError::WrongNumberCount { location, found } => write!(
+                f,
+                "{location}: expected destination start, source start & range length, found {found} numbers"
+            ),
+            ParseError::RangeOverflow {
+                location,
+                start,
+                length,
+            } => write!(
+                f,
+                "{location}: the {length} numbers from {start} go past the largest supported number, {}",
+                u64::MAX
+            ),
+            ParseError::DuplicateMap {
+                location,
+                source,
+                first,
+            } => write!(
+                f,
+                "{location}: there's already a map from `{source}`, at {first}"
+            ),
+            ParseError::Path(error) => write!(f, "{error}"),
         }
-        previous_subrange = cur_subrange;
     }
-    if previous_subrange.end() < range.end() {
-        not_covered_ranges.push((first_subrange.end() + 1)..=*range.end());
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<PathError> for ParseError {
+    fn from(error: PathError) -> Self {
+        ParseError::Path(error)
     }
-    not_covered_ranges
 }
 
-pub fn generator(input: &str) -> (Seeds, GardenAlmanac) {
-    let expectation = "Input should be in prescribed format";
-    let mut lines = input.lines();
-    let seeds = lines
-        .next()
-        .expect(expectation)
-        .split_once(':')
-        .expect(expectation)
-        .1
-        .split_whitespace()
-        .map(|num_str| num_str.parse::<u32>().expect(expectation))
-        .collect();
-    lines.next();
-    let mut mappings = Vec::new();
-    for _ in 0..7 {
-        lines.next();
-        let mut mapping = Vec::new();
-        for map_line in lines.by_ref() {
-            if map_line.trim().is_empty() {
-                break;
+#[derive(Debug, PartialEq, Eq)]
+pub enum PathError {
+    /// There's no map out of `stuck_at`, on the way from `from` to `to`
+    Missing {
+        from: String,
+        to: String,
+        stuck_at: String,
+    },
+    /// The maps out of `from` lead back to `category` without reaching `to`
+    Cyclic {
+        from: String,
+        to: String,
+        category: String,
+    },
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathError::Missing { from, to, stuck_at } => write!(
+                f,
+                "no map from `{stuck_at}`, on the way from `{from}` to `{to}`"
+            ),
+            PathError::Cyclic { from, to, category } => write!(
+                f,
+                "the maps from `{from}` go round in circles through `{category}` without reaching `{to}`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Something about a range line of a map that [`GardenAlmanac::validate`] reports
+#[derive(Debug, PartialEq, Eq)]
+pub enum Issue {
+    /// A line that the [`generator`] rejects
+    Malformed(ParseError),
+    /// A range of length 0, which maps nothing
+    ZeroLength { location: Location },
+    /// Numbers of `overlap` are in the source range of an earlier line too, so this line doesn't
+    /// apply to them
+    OverlappingSources {
+        location: Location,
+        first: Location,
+        overlap: RangeInclusive<u64>,
+    },
+    /// Numbers of `overlap` are in the destination range of an earlier line too, so each of them
+    /// comes from more than one source
+    OverlappingDestinations {
+        location: Location,
+        first: Location,
+        overlap: RangeInclusive<u64>,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Issue::Malformed(error) => write!(f, "{error}"),
+            Issue::ZeroLength { location } => {
+                write!(f, "{location}: the range is empty, so it maps nothing")
             }
-            let partial_mapping = map_line
-                .split_whitespace()
-                .map(|num_str| num_str.parse::<u32>().expect(expectation))
-                .collect::<Vec<u32>>();
-            mapping.push(partial_mapping);
+            Issue::OverlappingSources {
+                location,
+                first,
+                overlap,
+            } => write!(
+                f,
+                "{location}: {overlap:?} is already mapped at {first}, so this line doesn't apply to it"
+            ),
+            Issue::OverlappingDestinations {
+                location,
+                first,
+                overlap,
+            } => write!(
+                f,
+                "{location}: {overlap:?} is already mapped onto at {first}, so it has more than one source"
+            ),
+        }
+    }
+}
+
+/// What [`GardenAlmanac::validate`] found about a map
+#[derive(Debug, PartialEq, Eq)]
+pub struct MapReport {
+    pub source: String,
+    pub destination: String,
+    /// Location of the `source-to-destination map:` header
+    pub location: Location,
+    pub issues: Vec<Issue>,
+    /// Numbers that no range of the map covers, which are left as they are
+    pub identity: RangeSet,
+}
+
+impl fmt::Display for MapReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {}-to-{} map",
+            self.location, self.source, self.destination
+        )?;
+        for issue in &self.issues {
+            writeln!(f, "  {issue}")?;
+        }
+        let identity = self
+            .identity
+            .ranges()
+            .iter()
+            .map(|range| format!("{range:?}"))
+            .collect::<Vec<_>>();
+        writeln!(f, "  left as they are: {}", identity.join(", "))
+    }
+}
+
+fn parse_numbers(line: &Line, numbers: &str) -> Result<Vec<u64>, ParseError> {
+    numbers
+        .split_whitespace()
+        .map(|num_str| {
+            num_str.parse().map_err(|_| ParseError::InvalidNumber {
+                location: line.locate(num_str),
+                found: num_str.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Checks that the `length` numbers from `start` don't go past `u64::MAX`, `at` being the start in
+/// the line
+fn check_range(line: &Line, at: &str, start: u64, length: u64) -> Result<(), ParseError> {
+    match length.checked_sub(1) {
+        Some(last_offset) if start.checked_add(last_offset).is_none() => {
+            Err(ParseError::RangeOverflow {
+                location: line.locate(at),
+                start,
+                length,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A `source-to-destination map:` section of the input, with its range lines left unread
+struct Section<'a> {
+    header: Line<'a>,
+    source: &'a str,
+    destination: &'a str,
+    range_lines: Vec<Line<'a>>,
+}
+
+/// Reads the seeds & splits the rest of the input into map sections, checking everything but the
+/// range lines of the maps
+fn read_sections(input: &str) -> Result<(Seeds, Vec<Section<'_>>), ParseError> {
+    let mut lines = parse::lines(input);
+    let seeds_line = lines.next().ok_or(ParseError::MissingPrefix {
+        location: parse::end_of_input(input),
+        expected: "seeds:",
+    })?;
+    let seeds_text = seeds_line
+        .text
+        .strip_prefix("seeds:")
+        .ok_or(ParseError::MissingPrefix {
+            location: seeds_line.start(),
+            expected: "seeds:",
+        })?;
+    let seeds = parse_numbers(&seeds_line, seeds_text)?;
+    for (range, start) in seeds
+        .chunks_exact(2)
+        .zip(seeds_text.split_whitespace().step_by(2))
+    {
+        check_range(&seeds_line, start, range[0], range[1])?;
+    }
+    if seed_ranges(&seeds).next().is_none() {
+        return Err(ParseError::MissingSeeds {
+            location: seeds_line.end(),
+        });
+    }
+    let mut sections: Vec<Section> = Vec::new();
+    while let Some(header) = lines.find(|line| !line.text.trim().is_empty()) {
+        let (source, destination) = header
+            .text
+            .strip_suffix(" map:")
+            .and_then(|categories| categories.split_once("-to-"))
+            .ok_or(ParseError::MissingMapHeader {
+                location: header.start(),
+            })?;
+        if let Some(first) = sections.iter().find(|section| section.source == source) {
+            return Err(ParseError::DuplicateMap {
+                location: header.start(),
+                source: source.to_string(),
+                first: first.header.start(),
+            });
+        }
+        let range_lines = lines
+            .by_ref()
+            .take_while(|line| !line.text.trim().is_empty())
+            .collect();
+        sections.push(Section {
+            header,
+            source,
+            destination,
+            range_lines,
+        });
+    }
+    Ok((seeds, sections))
+}
+
+/// Reads a `destination start, source start, length` line of a map
+fn parse_range_line(line: &Line) -> Result<RangeMapping, ParseError> {
+    let numbers = parse_numbers(line, line.text)?;
+    let [destination, source, length] = numbers[..] else {
+        return Err(ParseError::WrongNumberCount {
+            location: line.start(),
+            found: numbers.len(),
+        });
+    };
+    RangeMapping::new(destination, source, length).map_err(|OverflowError { start, length }| {
+        let starts = line.text.split_whitespace().collect::<Vec<_>>();
+        ParseError::RangeOverflow {
+            location: line.locate(if start == destination {
+                starts[0]
+            } else {
+                starts[1]
+            }),
+            start,
+            length,
         }
-        mappings.push(mapping);
-    }
-    (
-        seeds,
-        GardenAlmanac {
-            seed_to_soil: mappings.remove(0),
-            soil_to_fertilizer: mappings.remove(0),
-            fertilizer_to_water: mappings.remove(0),
-            water_to_light: mappings.remove(0),
-            light_to_temperature: mappings.remove(0),
-            temperature_to_humidity: mappings.remove(0),
-            humidity_to_location: mappings.remove(0),
-        },
-    )
-}
-
-pub fn part1((seeds, almanac): &(Seeds, GardenAlmanac)) -> u32 {
+    })
+}
+
+pub fn generator(input: &str) -> Result<(Seeds, GardenAlmanac), ParseError> {
+    let (seeds, sections) = read_sections(input)?;
+    let maps = sections
+        .into_iter()
+        .map(|section| {
+            Ok(CategoryMap {
+                source: section.source.to_string(),
+                destination: section.destination.to_string(),
+                mapping: section
+                    .range_lines
+                    .iter()
+                    .map(parse_range_line)
+                    .collect::<Result<_, _>>()?,
+            })
+        })
+        .collect::<Result<_, ParseError>>()?;
+    Ok((seeds, GardenAlmanac::new(maps)?))
+}
+
+/// # Panics
+///
+/// If there are no seeds, which the [`generator`] doesn't let through
+pub fn part1((seeds, almanac): &(Seeds, GardenAlmanac)) -> u64 {
     seeds
         .iter()
         .map(|seed| almanac.get_location_for_seed(*seed))
         .min()
-        .expect("There should be atleast one seed, and every seed should have at least one location mapping")
+        .expect("the generator only accepts seeds with a non-empty range")
 }
 
-#[allow(dead_code)]
-pub fn part2_naive((seeds, almanac): &(Seeds, GardenAlmanac)) -> u32 {
-    std::iter::zip(
-        seeds.iter().enumerate().filter(|(i, _)| i % 2 == 0).map(|(_, seed)| seed),
-        seeds.iter().enumerate().filter(|(i, _)| i % 2 != 0).map(|(_, seed)| seed)
-    ).filter_map(|(seed_start, seed_count)| {
-        (*seed_start..*seed_start+*seed_count)
-        .map(|seed| almanac.get_location_for_seed(seed))
-        .min()
+fn overlap(this: &RangeInclusive<u64>, other: &RangeInclusive<u64>) -> Option<RangeInclusive<u64>> {
+    let start = *this.start().max(other.start());
+    let end = *this.end().min(other.end());
+    (start <= end).then_some(start..=end)
+}
+
+/// Seeds read as pairs of range start & length, skipping empty ranges
+fn seed_ranges(seeds: &Seeds) -> impl Iterator<Item = RangeInclusive<u64>> + '_ {
+    seeds.chunks_exact(2).filter_map(|range| {
+        let last_offset = range[1].checked_sub(1)?;
+        Some(range[0]..=range[0] + last_offset)
     })
-        .min()
-        .expect("There should be atleast one seed, and every seed should have at least one location mapping")
 }
 
-#[allow(clippy::range_minus_one)] // Using inclusive ranges here makes the code simpler
-pub fn part2((seeds, almanac): &(Seeds, GardenAlmanac)) -> u32 {
-    std::iter::zip(
-        seeds.iter().enumerate().filter(|(i, _)| i % 2 == 0).map(|(_, seed)| seed),
-        seeds.iter().enumerate().filter(|(i, _)| i % 2 != 0).map(|(_, seed)| seed),
-    )
-        .flat_map(|(seed_start, seed_count)| {
-            almanac.get_location_ranges_for_seed_range(*seed_start..=*seed_start+*seed_count-1)
+/// # Panics
+///
+/// Like [`part2`]
+pub fn part2_naive((seeds, almanac): &(Seeds, GardenAlmanac)) -> u64 {
+    seed_ranges(seeds)
+        .filter_map(|seed_range| {
+            seed_range
+                .map(|seed| almanac.get_location_for_seed(seed))
+                .min()
         })
-        .min_by(|this_range, other_range| this_range.start().cmp(other_range.start()))
-        .map(|min_start_range| *min_start_range.start())
-        .expect("There should be atleast one seed, and every seed should have at least one location mapping")
+        .min()
+        .expect("the generator only accepts seeds with a non-empty range")
+}
+
+/// # Panics
+///
+/// If no pair of seeds makes a non-empty range, which the [`generator`] doesn't let through
+pub fn part2((seeds, almanac): &(Seeds, GardenAlmanac)) -> u64 {
+    seed_ranges(seeds)
+        .filter_map(|seed_range| almanac.get_location_ranges_for_seed_range(seed_range).min())
+        .min()
+        .expect("the generator only accepts seeds with a non-empty range")
+}
+
+/// # Panics
+///
+/// Like [`part1`]
+pub fn part1_compiled((seeds, almanac): &(Seeds, GardenAlmanac)) -> u64 {
+    let compiled = almanac.compile();
+    seeds
+        .iter()
+        .map(|seed| compiled.apply(*seed))
+        .min()
+        .expect("the generator only accepts seeds with a non-empty range")
+}
+
+/// # Panics
+///
+/// Like [`part2`]
+pub fn part2_compiled((seeds, almanac): &(Seeds, GardenAlmanac)) -> u64 {
+    almanac
+        .compile()
+        .apply_to_ranges(&seed_ranges(seeds).collect())
+        .min()
+        .expect("the generator only accepts seeds with a non-empty range")
 }
 
-#[allow(clippy::range_minus_one)] // Using inclusive ranges here makes the code simpler
-pub fn part1_with_ranges((seeds, almanac): &(Seeds, GardenAlmanac)) -> u32 {
-    seeds.iter()
-        .flat_map(|seed_start| {
-            almanac.get_location_ranges_for_seed_range(*seed_start..=*seed_start)
+/// # Panics
+///
+/// Like [`part1`]
+pub fn part1_with_ranges((seeds, almanac): &(Seeds, GardenAlmanac)) -> u64 {
+    seeds
+        .iter()
+        .filter_map(|seed_start| {
+            almanac
+                .get_location_ranges_for_seed_range(*seed_start..=*seed_start)
+                .min()
         })
-        .min_by(|this_range, other_range| this_range.start().cmp(other_range.start()))
-        .map(|min_start_range| *min_start_range.start())
-        .expect("There should be atleast one seed, and every seed should have at least one location mapping")
+        .min()
+        .expect("the generator only accepts seeds with a non-empty range")
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 5;
+    type Parsed = (Seeds, GardenAlmanac);
+    type Answer = u64;
+    type Error = ParseError;
+    const PARTS: [&'static [Variant<Self>]; 2] = [
+        variants![part1, part1_with_ranges, part1_compiled],
+        variants![part2, part2_compiled; slow: part2_naive],
+    ];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error> {
+        generator(input)
+    }
 }
 
 #[cfg(test)]
@@ -270,9 +1010,18 @@ mod tests {
 
     use super::*;
     use indoc::indoc;
-    use std::collections::HashSet;
 
-    const EXAMPLE_INPUT: &str = indoc! {"
+    /// Mapping of `[destination, source, length]` rows
+    pub(super) fn mapping<const N: usize>(rows: [[u64; 3]; N]) -> Mapping {
+        rows.into_iter()
+            .map(|[destination, source, length]| {
+                RangeMapping::new(destination, source, length)
+                    .expect("test range mappings should fit in a u64")
+            })
+            .collect()
+    }
+
+    pub(super) const EXAMPLE_INPUT: &str = indoc! {"
             seeds: 79 14 55 13
 
             seed-to-soil map:
@@ -311,69 +1060,438 @@ mod tests {
     #[test]
     fn generator_example() {
         // println!("{:?}", generator(EXAMPLE_INPUT));
-        let mut expected_mappings = vec![
-            vec![vec![50, 98, 2], vec![52, 50, 48]],
-            vec![vec![0, 15, 37], vec![37, 52, 2], vec![39, 0, 15]],
-            vec![
-                vec![49, 53, 8],
-                vec![0, 11, 42],
-                vec![42, 0, 7],
-                vec![57, 7, 4],
-            ],
-            vec![vec![88, 18, 7], vec![18, 25, 70]],
-            vec![vec![45, 77, 23], vec![81, 45, 19], vec![68, 64, 13]],
-            vec![vec![0, 69, 1], vec![1, 0, 69]],
-            vec![vec![60, 56, 37], vec![56, 93, 4]],
+        let categories = [
+            "seed",
+            "soil",
+            "fertilizer",
+            "water",
+            "light",
+            "temperature",
+            "humidity",
+            "location",
         ];
+        let expected_mappings = vec![
+            mapping([[50, 98, 2], [52, 50, 48]]),
+            mapping([[0, 15, 37], [37, 52, 2], [39, 0, 15]]),
+            mapping([[49, 53, 8], [0, 11, 42], [42, 0, 7], [57, 7, 4]]),
+            mapping([[88, 18, 7], [18, 25, 70]]),
+            mapping([[45, 77, 23], [81, 45, 19], [68, 64, 13]]),
+            mapping([[0, 69, 1], [1, 0, 69]]),
+            mapping([[60, 56, 37], [56, 93, 4]]),
+        ];
+        let expected_maps = categories
+            .windows(2)
+            .zip(expected_mappings)
+            .map(|(pair, mapping)| CategoryMap {
+                source: pair[0].to_string(),
+                destination: pair[1].to_string(),
+                mapping,
+            })
+            .collect();
         let expected = (
             vec![79, 14, 55, 13],
-            GardenAlmanac {
-                seed_to_soil: expected_mappings.remove(0),
-                soil_to_fertilizer: expected_mappings.remove(0),
-                fertilizer_to_water: expected_mappings.remove(0),
-                water_to_light: expected_mappings.remove(0),
-                light_to_temperature: expected_mappings.remove(0),
-                temperature_to_humidity: expected_mappings.remove(0),
-                humidity_to_location: expected_mappings.remove(0),
-            },
+            GardenAlmanac::new(expected_maps).expect("maps should lead from seed to location"),
         );
-        assert_eq!(generator(EXAMPLE_INPUT), expected);
+        assert_eq!(generator(EXAMPLE_INPUT), Ok(expected));
     }
 
     #[test]
-    fn part1_example() {
-        assert_eq!(part1(&generator(EXAMPLE_INPUT)), 35);
-        assert_eq!(part1_with_ranges(&generator(EXAMPLE_INPUT)), 35);
+    fn generator_errors() {
+        let truncated_input = &EXAMPLE_INPUT[..EXAMPLE_INPUT.find("light-to").unwrap_or(0)];
+        assert_eq!(
+            generator(truncated_input).err(),
+            Some(ParseError::Path(PathError::Missing {
+                from: "seed".to_string(),
+                to: "location".to_string(),
+                stuck_at: "light".to_string(),
+            }))
+        );
+        assert_eq!(
+            generator(&EXAMPLE_INPUT.replace("seeds: 79 14 55 13", "seeds:")).err(),
+            Some(ParseError::MissingSeeds {
+                location: Location { line: 1, column: 7 },
+            })
+        );
+        assert_eq!(
+            generator(&EXAMPLE_INPUT.replace("seeds: 79 14 55 13", "seeds: 79 0 55")).err(),
+            Some(ParseError::MissingSeeds {
+                location: Location {
+                    line: 1,
+                    column: 15
+                },
+            })
+        );
+        let duplicated_input = EXAMPLE_INPUT.replace("water-to-light", "soil-to-light");
+        assert_eq!(
+            generator(&duplicated_input).err(),
+            Some(ParseError::DuplicateMap {
+                location: Location {
+                    line: 18,
+                    column: 1
+                },
+                source: "soil".to_string(),
+                first: Location { line: 7, column: 1 },
+            })
+        );
+        let misnamed_input = EXAMPLE_INPUT.replace("water-to-light", "water light");
+        assert_eq!(
+            generator(&misnamed_input).err(),
+            Some(ParseError::MissingMapHeader {
+                location: Location {
+                    line: 18,
+                    column: 1
+                },
+            })
+        );
+        let corrupted_input = EXAMPLE_INPUT.replace("37 52 2", "37 52");
+        assert_eq!(
+            generator(&corrupted_input).err(),
+            Some(ParseError::WrongNumberCount {
+                location: Location { line: 9, column: 1 },
+                found: 2,
+            })
+        );
+        let overflowing_input = EXAMPLE_INPUT.replace("37 52 2", "18446744073709551615 52 2");
+        assert_eq!(
+            generator(&overflowing_input).err(),
+            Some(ParseError::RangeOverflow {
+                location: Location { line: 9, column: 1 },
+                start: u64::MAX,
+                length: 2,
+            })
+        );
+        let overflowing_input = EXAMPLE_INPUT.replace("55 13", "18446744073709551600 17");
+        assert_eq!(
+            generator(&overflowing_input).err(),
+            Some(ParseError::RangeOverflow {
+                location: Location {
+                    line: 1,
+                    column: 14
+                },
+                start: 18_446_744_073_709_551_600,
+                length: 17,
+            })
+        );
     }
 
     #[test]
-    fn apply_mapping_to_range_doc_example() {
-        assert_eq!(
-            GardenAlmanac::apply_mapping_to_range(
-                &(25..=47), // i
-                &vec![
-                    vec![28, 30, 7], // x
-                    vec![50, 37, 4], // y
-                    vec![41, 44, 6], // z
-                ]
-            )
-            .iter()
-            .collect::<HashSet<_>>(),
+    fn validates_maps() {
+        let reports =
+            GardenAlmanac::validate(EXAMPLE_INPUT).expect("example should be a valid almanac");
+        assert_eq!(reports.len(), 7);
+        assert!(reports.iter().all(|report| report.issues.is_empty()));
+        assert_eq!(reports[0].identity.ranges(), [0..=49, 100..=u64::MAX]);
+
+        let input = indoc! {"
+            seeds: 79 14
+
+            seed-to-soil map:
+            50 98 2
+            52 50 48
+            60 90 10
+            1 2
+
+            soil-to-fertilizer map:
+            0 15 0
+        "};
+        let at_line = |line| Location { line, column: 1 };
+        let reports = GardenAlmanac::validate(input).expect("sections should be readable");
+        assert_eq!(
+            reports[0].issues,
             [
-                25..=29, // a
-                28..=34, // b
-                50..=53, // c
-                41..=43, // d
-                41..=44, // e
+                Issue::OverlappingSources {
+                    location: at_line(6),
+                    first: at_line(4),
+                    overlap: 98..=99,
+                },
+                Issue::OverlappingSources {
+                    location: at_line(6),
+                    first: at_line(5),
+                    overlap: 90..=97,
+                },
+                Issue::OverlappingDestinations {
+                    location: at_line(6),
+                    first: at_line(5),
+                    overlap: 60..=69,
+                },
+                Issue::Malformed(ParseError::WrongNumberCount {
+                    location: at_line(7),
+                    found: 2,
+                }),
             ]
-            .iter()
-            .collect::<HashSet<_>>()
+        );
+        assert_eq!(
+            reports[1].to_string(),
+            indoc! {"
+                line 9, column 1: soil-to-fertilizer map
+                  line 10, column 1: the range is empty, so it maps nothing
+                  left as they are: 0..=18446744073709551615
+            "}
+        );
+        assert!(generator(input).is_err());
+    }
+
+    #[test]
+    fn maps_between_any_connected_categories() {
+        let (_, almanac) = generator(EXAMPLE_INPUT).expect("example should be a valid almanac");
+        let soil_to_light = almanac
+            .mapping("soil", "light")
+            .expect("soil should lead to light");
+        let path = almanac
+            .path("soil", "light")
+            .expect("soil should lead to light");
+        assert_eq!(
+            path.iter()
+                .map(|map| map.destination.as_str())
+                .collect::<Vec<_>>(),
+            ["fertilizer", "water", "light"]
+        );
+        for soil in 0..120 {
+            let light = path.iter().fold(soil, |number, map| {
+                GardenAlmanac::apply_mapping(number, &map.mapping)
+            });
+            assert_eq!(soil_to_light.apply(soil), light, "soil {soil}");
+        }
+        assert_eq!(
+            almanac.mapping("water", "water"),
+            Ok(CompiledMapping::default())
+        );
+        assert_eq!(
+            almanac.path("location", "seed").err(),
+            Some(PathError::Missing {
+                from: "location".to_string(),
+                to: "seed".to_string(),
+                stuck_at: "location".to_string(),
+            })
+        );
+
+        let cyclic_input =
+            format!("{EXAMPLE_INPUT}\nhumus-to-compost map:\n\ncompost-to-humus map:\n");
+        let (_, almanac) =
+            generator(&cyclic_input).expect("cycles off the seed to location path are fine");
+        assert_eq!(
+            almanac.path("humus", "seed").err(),
+            Some(PathError::Cyclic {
+                from: "humus".to_string(),
+                to: "seed".to_string(),
+                category: "humus".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn mapping_down_from_the_top_of_the_range() {
+        let input = indoc! {"
+            seeds: 18446744073709551610 6
+
+            seed-to-soil map:
+            0 18446744073709551600 16
+
+            soil-to-fertilizer map:
+
+            fertilizer-to-water map:
+
+            water-to-light map:
+
+            light-to-temperature map:
+
+            temperature-to-humidity map:
+
+            humidity-to-location map:
+        "};
+        let input = generator(input).expect("input should be a valid almanac");
+        assert_eq!(part1(&input), 6);
+        assert_eq!(part1_with_ranges(&input), 6);
+        assert_eq!(part2(&input), 10);
+        assert_eq!(part2_naive(&input), 10);
+        assert_eq!(part1_compiled(&input), 6);
+        assert_eq!(part2_compiled(&input), 10);
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(generator(EXAMPLE_INPUT).map(|input| part1(&input)), Ok(35));
+        assert_eq!(
+            generator(EXAMPLE_INPUT).map(|input| part1_with_ranges(&input)),
+            Ok(35)
+        );
+        assert_eq!(
+            generator(EXAMPLE_INPUT).map(|input| part1_compiled(&input)),
+            Ok(35)
+        );
+    }
+
+    #[test]
+    fn range_mappings_stay_in_the_u64_range() {
+        assert_eq!(
+            RangeMapping::new(u64::MAX - 1, 0, 3),
+            Err(OverflowError {
+                start: u64::MAX - 1,
+                length: 3
+            })
+        );
+        assert_eq!(
+            RangeMapping::new(0, u64::MAX, 2),
+            Err(OverflowError {
+                start: u64::MAX,
+                length: 2
+            })
+        );
+        let last = RangeMapping::new(u64::MAX - 1, 0, 2).expect("should end at u64::MAX");
+        assert_eq!(last.destination_range(), Some(u64::MAX - 1..=u64::MAX));
+        assert_eq!(last.apply(1), Some(u64::MAX));
+        assert_eq!(last.apply(2), None);
+        let empty = RangeMapping::new(u64::MAX, u64::MAX, 0).expect("should move nothing");
+        assert_eq!(empty.source_range(), None);
+        assert_eq!(
+            GardenAlmanac::apply_mapping_to_ranges(&RangeSet::from(0..=9), &vec![empty]).ranges(),
+            [0..=9]
+        );
+    }
+
+    #[test]
+    fn overlapping_sources_map_like_single_numbers() {
+        // 15..=19 is in both sources, & only the first range mapping applies to it
+        let mapping = mapping([[100, 10, 10], [200, 15, 10]]);
+        let output = GardenAlmanac::apply_mapping_to_ranges(&RangeSet::from(0..=30), &mapping);
+        assert_eq!(output.ranges(), [0..=9, 25..=30, 100..=109, 205..=209]);
+        assert_eq!(
+            output,
+            (0..=30)
+                .map(|number| {
+                    let mapped = GardenAlmanac::apply_mapping(number, &mapping);
+                    mapped..=mapped
+                })
+                .collect()
+        );
+    }
+
+    #[test]
+    fn location_ranges_stay_coalesced() {
+        let (_, almanac) = generator(EXAMPLE_INPUT).expect("example should be a valid almanac");
+        // Every seed gets a location, so the whole number line maps to the whole number line
+        assert_eq!(
+            almanac
+                .get_location_ranges_for_seed_range(0..=u64::MAX)
+                .ranges(),
+            [0..=u64::MAX]
+        );
+        assert_eq!(
+            almanac.get_location_ranges_for_seed_range(79..=92).ranges(),
+            [46..=55, 60..=60, 82..=84]
         );
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2_naive(&generator(EXAMPLE_INPUT)), 46);
-        assert_eq!(part2(&generator(EXAMPLE_INPUT)), 46);
+        assert_eq!(
+            generator(EXAMPLE_INPUT).map(|input| part2_naive(&input)),
+            Ok(46)
+        );
+        assert_eq!(generator(EXAMPLE_INPUT).map(|input| part2(&input)), Ok(46));
+        assert_eq!(
+            generator(EXAMPLE_INPUT).map(|input| part2_compiled(&input)),
+            Ok(46)
+        );
+    }
+
+    #[test]
+    fn compiles_mappings_into_segments() {
+        let seed_to_soil = CompiledMapping::from_mapping(&mapping([[50, 98, 2], [52, 50, 48]]));
+        assert_eq!(
+            seed_to_soil.to_string(),
+            indoc! {"
+                0..=49 +0
+                50..=97 +2
+                98..=99 -48
+                100..=18446744073709551615 +0
+            "}
+        );
+        // Shifting 50..=97 up by 2 then down by 2 leaves it where it was
+        let there_and_back =
+            seed_to_soil.then(&CompiledMapping::from_mapping(&mapping([[50, 52, 48]])));
+        assert_eq!(
+            there_and_back.segments(),
+            [
+                Segment {
+                    source: 0..=97,
+                    offset: 0
+                },
+                Segment {
+                    source: 98..=99,
+                    offset: -48
+                },
+                Segment {
+                    source: 100..=u64::MAX,
+                    offset: 0
+                },
+            ]
+        );
+
+        let (seeds, almanac) = generator(EXAMPLE_INPUT).expect("example should be a valid almanac");
+        let compiled = almanac.compile();
+        for seed in seeds.iter().copied().chain(0..200) {
+            assert_eq!(compiled.apply(seed), almanac.get_location_for_seed(seed));
+        }
+        assert_eq!(
+            compiled.apply_to_ranges(&RangeSet::from(79..=92)),
+            almanac.get_location_ranges_for_seed_range(79..=92)
+        );
+    }
+
+    #[test]
+    fn finds_seeds_for_locations() {
+        let (seeds, almanac) = generator(EXAMPLE_INPUT).expect("example should be a valid almanac");
+        let below_50 = RangeSet::from(0..=49);
+        let all_seeds = almanac.seeds_for_locations(&below_50, None);
+        assert_eq!(
+            all_seeds.ranges(),
+            [0..=21, 26..=43, 50..=53, 70..=70, 82..=85, 99..=99]
+        );
+        for seed in 0..300 {
+            assert_eq!(
+                all_seeds.contains(seed),
+                below_50.contains(almanac.get_location_for_seed(seed)),
+                "seed {seed}"
+            );
+        }
+        assert_eq!(
+            almanac
+                .seeds_for_locations(&below_50, Some(&seeds))
+                .ranges(),
+            [82..=85]
+        );
+        assert!(almanac
+            .seeds_for_locations(&RangeSet::from(0..=45), Some(&seeds))
+            .is_empty());
+    }
+
+    #[test]
+    fn compiled_mappings_round_trip() {
+        let (_, almanac) = generator(EXAMPLE_INPUT).expect("example should be a valid almanac");
+        let compiled = almanac.compile();
+        let json = serde_json::to_string(&compiled).expect("compiled mapping should serialize");
+        assert_eq!(
+            serde_json::from_str::<CompiledMapping>(&json).ok(),
+            Some(compiled)
+        );
+
+        let error = |json| {
+            serde_json::from_str::<CompiledMapping>(json)
+                .err()
+                .map(|error| error.to_string())
+        };
+        assert_eq!(
+            error(r#"[{"source": {"start": 1, "end": 18446744073709551615}, "offset": 0}]"#),
+            Some("expected a segment starting at 0, found 1..=18446744073709551615".to_string())
+        );
+        assert_eq!(
+            error(r#"[{"source": {"start": 0, "end": 18446744073709551615}, "offset": 1}]"#),
+            Some("0..=18446744073709551615 moved by 1 goes out of the u64 range".to_string())
+        );
+        assert_eq!(
+            error(r#"[{"source": {"start": 0, "end": 10}, "offset": 0}]"#),
+            Some("expected the segments to go up to the largest u64".to_string())
+        );
     }
 }
@@ -0,0 +1,350 @@
+//! Ground truth for part 2: every seed of the seed ranges mapped one by one, through the compiled
+//! mapping, on all cores
+//!
+//! The seeds are handed out to the threads in chunks, and the chunks that are done get recorded in
+//! a [`Checkpoint`] that can be saved, so that a run can be resumed where it stopped.
+
+use super::{seed_ranges, GardenAlmanac, Seeds};
+use crate::{answers::input_hash, range_set::RangeSet};
+use std::{
+    fmt, fs, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_CHECKPOINT_FILE: &str = "day5_checkpoint.txt";
+/// Seeds that a thread goes through before reporting back
+const CHUNK_SIZE: u64 = 1 << 22;
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Invalid {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckpointError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            CheckpointError::Invalid {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{line}: {message}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CheckpointError::Io { source, .. } => Some(source),
+            CheckpointError::Invalid { .. } => None,
+        }
+    }
+}
+
+/// Seeds gone through so far, along with the lowest location they map to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Hash of the input the seeds are from, as a checkpoint is only good for that input
+    pub input_hash: String,
+    pub done: RangeSet,
+    pub min_location: Option<u64>,
+}
+
+impl Checkpoint {
+    pub fn new(input: &str) -> Self {
+        Self {
+            input_hash: input_hash(input),
+            done: RangeSet::new(),
+            min_location: None,
+        }
+    }
+
+    /// Checkpoint saved at `path`, or a new one if there's no file there. A checkpoint saved for
+    /// another input is an error rather than being overwritten.
+    pub fn load(path: &Path, input: &str) -> Result<Self, CheckpointError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::new(input)),
+            Err(source) => {
+                return Err(CheckpointError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        let invalid = |line, message| CheckpointError::Invalid {
+            path: path.to_path_buf(),
+            line,
+            message,
+        };
+        let checkpoint =
+            Self::from_text(&contents).map_err(|(line, message)| invalid(line, message))?;
+        if checkpoint.input_hash != input_hash(input) {
+            return Err(invalid(
+                1,
+                format!(
+                    "the checkpoint is for another input, remove it to start over on this one ({})",
+                    input_hash(input)
+                ),
+            ));
+        }
+        Ok(checkpoint)
+    }
+
+    /// Saves the checkpoint at `path` through a temporary file, so that a run stopped while
+    /// saving doesn't leave half a checkpoint behind
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let io_error = |source| CheckpointError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, self.to_text()).map_err(io_error)?;
+        fs::rename(&temporary, path).map_err(io_error)
+    }
+
+    fn to_text(&self) -> String {
+        let mut lines = vec![format!("input_hash {}", self.input_hash)];
+        lines.extend(
+            self.min_location
+                .map(|min_location| format!("min_location {min_location}")),
+        );
+        lines.extend(
+            self.done
+                .ranges()
+                .iter()
+                .map(|range| format!("done {}..={}", range.start(), range.end())),
+        );
+        lines.join("\n") + "\n"
+    }
+
+    /// Reads back [`Self::to_text`], failing with the line number & what's wrong with it
+    fn from_text(text: &str) -> Result<Self, (usize, String)> {
+        let mut input_hash = None;
+        let mut min_location = None;
+        let mut done = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let invalid = |message| (index + 1, message);
+            match line.split_once(' ') {
+                Some(("input_hash", hash)) => input_hash = Some(hash.to_string()),
+                Some(("min_location", location)) => {
+                    min_location = Some(number(location).map_err(invalid)?);
+                }
+                Some(("done", range)) => done.push(range_of(range).map_err(invalid)?),
+                _ => return Err(invalid(format!("unexpected line `{line}`"))),
+            }
+        }
+        Ok(Self {
+            input_hash: input_hash.ok_or((1, "missing the input hash".to_string()))?,
+            done: done.into_iter().collect(),
+            min_location,
+        })
+    }
+}
+
+fn number(text: &str) -> Result<u64, String> {
+    text.parse()
+        .map_err(|_| format!("expected a number, found `{text}`"))
+}
+
+fn range_of(text: &str) -> Result<RangeInclusive<u64>, String> {
+    let (start, end) = text
+        .split_once("..=")
+        .ok_or(format!("expected a range like `0..=9`, found `{text}`"))?;
+    Ok(number(start)?..=number(end)?)
+}
+
+/// How far along a run is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Seeds done, including those done before resuming
+    pub done: u128,
+    pub total: u128,
+    /// Seeds that were already done when the run started
+    pub resumed: u128,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// Time left going by the pace so far, unless nothing got done yet
+    #[allow(clippy::cast_precision_loss)] // Only an estimate
+    pub fn eta(&self) -> Option<Duration> {
+        let done_in_run = self
+            .done
+            .checked_sub(self.resumed)
+            .filter(|done| *done > 0)?;
+        let left = self.total.saturating_sub(self.done);
+        Some(self.elapsed.mul_f64(left as f64 / done_in_run as f64))
+    }
+}
+
+impl fmt::Display for Progress {
+    #[allow(clippy::cast_precision_loss)] // Only a percentage
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let percent = 100.0 * self.done as f64 / self.total.max(1) as f64;
+        write!(f, "{percent:.1}% of {} seeds", self.total)?;
+        match self.eta() {
+            Some(eta) => {
+                let seconds = eta.as_secs();
+                write!(f, ", {}m {:02}s left", seconds / 60, seconds % 60)
+            }
+            None => write!(f, ", estimating time left"),
+        }
+    }
+}
+
+/// `ranges` cut into chunks of at most `CHUNK_SIZE` seeds
+fn chunks(ranges: &RangeSet) -> Vec<RangeInclusive<u64>> {
+    let mut chunks = Vec::new();
+    for range in ranges.ranges() {
+        let mut start = *range.start();
+        loop {
+            let end = start.saturating_add(CHUNK_SIZE - 1).min(*range.end());
+            chunks.push(start..=end);
+            match end.checked_add(1) {
+                Some(next) if next <= *range.end() => start = next,
+                _ => break,
+            }
+        }
+    }
+    chunks
+}
+
+/// Maps every seed of the seed ranges that isn't done in `checkpoint` on `threads` threads,
+/// recording each chunk of seeds in `checkpoint` as it gets done & then calling `report` with it.
+/// Returns the lowest location of all, unless there are no seeds.
+pub fn run(
+    almanac: &GardenAlmanac,
+    seeds: &Seeds,
+    threads: usize,
+    checkpoint: &mut Checkpoint,
+    mut report: impl FnMut(&Checkpoint, &Progress),
+) -> Option<u64> {
+    let compiled = almanac.compile();
+    let all_seeds = seed_ranges(seeds).collect::<RangeSet>();
+    let chunks = chunks(&all_seeds.difference(&checkpoint.done));
+    let next_chunk = AtomicUsize::new(0);
+    let mut progress = Progress {
+        done: all_seeds.intersection(&checkpoint.done).len(),
+        total: all_seeds.len(),
+        resumed: all_seeds.intersection(&checkpoint.done).len(),
+        elapsed: Duration::ZERO,
+    };
+    let start = Instant::now();
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..threads.max(1) {
+            let sender = sender.clone();
+            let (compiled, chunks, next_chunk) = (&compiled, &chunks, &next_chunk);
+            scope.spawn(move || {
+                while let Some(chunk) = chunks.get(next_chunk.fetch_add(1, Ordering::Relaxed)) {
+                    let min_location = chunk.clone().map(|seed| compiled.apply(seed)).min();
+                    if sender.send((chunk, min_location)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        // Only the workers hold senders now, so that the loop ends once they're all done
+        drop(sender);
+        for (chunk, min_location) in receiver {
+            checkpoint.done = checkpoint.done.union(&RangeSet::from(chunk.clone()));
+            checkpoint.min_location = checkpoint
+                .min_location
+                .into_iter()
+                .chain(min_location)
+                .min();
+            progress.done += u128::from(chunk.end() - chunk.start()) + 1;
+            progress.elapsed = start.elapsed();
+            report(checkpoint, &progress);
+        }
+    });
+    checkpoint.min_location
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::{generator, part2, tests::EXAMPLE_INPUT};
+
+    #[test]
+    fn matches_part2_and_resumes() {
+        let parsed = generator(EXAMPLE_INPUT).expect("example should be a valid almanac");
+        let (seeds, almanac) = &parsed;
+        let mut checkpoint = Checkpoint::new(EXAMPLE_INPUT);
+        let mut reports = Vec::new();
+        let min_location = run(almanac, seeds, 2, &mut checkpoint, |_, progress| {
+            reports.push(*progress);
+        });
+        assert_eq!(min_location, Some(part2(&parsed)));
+        assert_eq!(reports.len(), 2);
+        assert!(reports
+            .iter()
+            .any(|progress| progress.done == 27 && progress.total == 27));
+        assert_eq!(checkpoint.done.ranges(), [55..=67, 79..=92]);
+
+        // Seed 82 maps to the lowest location, 46, so it has to come from the checkpoint
+        let mut resumed = Checkpoint {
+            done: RangeSet::from(79..=92),
+            min_location: Some(46),
+            ..Checkpoint::new(EXAMPLE_INPUT)
+        };
+        let mut reports = Vec::new();
+        let min_location = run(almanac, seeds, 4, &mut resumed, |_, progress| {
+            reports.push(*progress);
+        });
+        assert_eq!(min_location, Some(46));
+        assert_eq!(
+            reports
+                .iter()
+                .map(|progress| (progress.resumed, progress.done))
+                .collect::<Vec<_>>(),
+            [(14, 27)]
+        );
+        assert_eq!(resumed, checkpoint);
+    }
+
+    #[test]
+    fn saves_and_loads_checkpoints() {
+        let directory = tempfile::tempdir().expect("should be able to create a temp dir");
+        let path = directory.path().join(DEFAULT_CHECKPOINT_FILE);
+        let checkpoint = Checkpoint::load(&path, EXAMPLE_INPUT).expect("no file means a new one");
+        assert_eq!(checkpoint, Checkpoint::new(EXAMPLE_INPUT));
+
+        let checkpoint = Checkpoint {
+            done: [55..=67, 79..=u64::MAX].into_iter().collect(),
+            min_location: Some(46),
+            ..checkpoint
+        };
+        checkpoint.save(&path).expect("checkpoint should save");
+        assert_eq!(
+            Checkpoint::load(&path, EXAMPLE_INPUT).ok(),
+            Some(checkpoint)
+        );
+        assert!(matches!(
+            Checkpoint::load(&path, "seeds: 1 2"),
+            Err(CheckpointError::Invalid { line: 1, .. })
+        ));
+        fs::write(&path, "input_hash 0\ndone 5..6\n").expect("should be able to write");
+        assert!(matches!(
+            Checkpoint::load(&path, EXAMPLE_INPUT),
+            Err(CheckpointError::Invalid { line: 2, .. })
+        ));
+    }
+}
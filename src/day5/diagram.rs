@@ -0,0 +1,228 @@
+//! Number-line diagrams of how a mapping splits & moves ranges of numbers, like the hand-drawn one
+//! on [`GardenAlmanac::apply_mapping_to_ranges`], for debugging range splits
+//!
+//! Each line of a mapping is labelled with a letter, `a` for the first one, which is used both for
+//! its source & destination ranges and for the pieces of the input that it moves. Pieces that no
+//! line moves are labelled with `=`.
+
+use super::{GardenAlmanac, Mapping, PathError};
+use crate::range_set::RangeSet;
+use std::{fmt::Write, ops::RangeInclusive};
+
+/// Width of the row labels, arrow included
+const LABEL_WIDTH: usize = 18;
+/// Narrowest number line drawn, however narrow the terminal
+const MIN_LINE_WIDTH: usize = 10;
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+/// Width of the terminal that stdout goes to, or when it goes elsewhere (like a pipe), of the
+/// `COLUMNS` env var, which shells don't export by default, or else 80 columns
+pub fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(width), _)| usize::from(width))
+        .or_else(|| {
+            std::env::var("COLUMNS")
+                .ok()
+                .and_then(|columns| columns.parse().ok())
+        })
+        .unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+
+/// Part of the number line drawn, spread over `width` columns
+struct Scale {
+    low: u64,
+    high: u64,
+    width: usize,
+}
+
+impl Scale {
+    fn column(&self, number: u64) -> usize {
+        let number = number.clamp(self.low, self.high);
+        let span = u128::from(self.high - self.low).max(1);
+        let column = u128::from(number - self.low) * (self.width as u128 - 1) / span;
+        usize::try_from(column).expect("columns are below the width")
+    }
+
+    fn row(&self) -> Vec<char> {
+        vec![' '; self.width]
+    }
+
+    /// Draws the part of `range` on this scale as `|fff|`, with `label` in the middle if it fits
+    fn draw(&self, row: &mut [char], range: &RangeInclusive<u64>, fill: char, label: char) {
+        let (start, end) = (*range.start().max(&self.low), *range.end().min(&self.high));
+        if start > end {
+            return;
+        }
+        let (first, last) = (self.column(start), self.column(end));
+        row[first..=last].fill(fill);
+        row[first] = '|';
+        row[last] = '|';
+        if last - first >= 2 {
+            row[first.midpoint(last)] = label;
+        }
+    }
+}
+
+/// Letter of the line at `index` of a mapping
+fn label(index: usize) -> char {
+    u8::try_from(index)
+        .ok()
+        .filter(|index| *index < 26)
+        .map_or('#', |index| char::from(b'a' + index))
+}
+
+fn push_row(diagram: &mut String, label: &str, row: &[char]) {
+    let row = row.iter().collect::<String>();
+    let line = format!("{label:<width$}-> {row}", width = LABEL_WIDTH - 3);
+    diagram.push_str(line.trim_end());
+    diagram.push('\n');
+}
+
+/// Diagram of `mapping` applied to `input`, scaled to fit in `width` columns
+///
+/// The number line spans the input & output ranges, with the ranges of the mapping cut down to
+/// that span. Numbers too close together to get columns of their own share one.
+pub fn render_mapping(input: &RangeSet, mapping: &Mapping, width: usize) -> String {
+    let mut unmapped = input.clone();
+    let mut pieces = Vec::new();
+    let mut lines = Vec::new();
+    for (index, range_mapping) in mapping.iter().enumerate() {
+        let (Some(source), Some(destination)) = (
+            range_mapping.source_range(),
+            range_mapping.destination_range(),
+        ) else {
+            continue;
+        };
+        let moved = range_mapping.apply_to_ranges(&unmapped);
+        unmapped = unmapped.difference(&RangeSet::from(source.clone()));
+        if !moved.is_empty() {
+            pieces.push((label(index), moved));
+        }
+        lines.push((label(index), source, destination));
+    }
+    if !unmapped.is_empty() {
+        pieces.push(('=', unmapped));
+    }
+    let output = pieces
+        .iter()
+        .fold(RangeSet::new(), |output, (_, piece)| output.union(piece));
+
+    let ranges = || input.ranges().iter().chain(output.ranges());
+    let scale = Scale {
+        low: ranges().map(|range| *range.start()).min().unwrap_or(0),
+        high: ranges().map(|range| *range.end()).max().unwrap_or(0),
+        width: width.saturating_sub(LABEL_WIDTH).max(MIN_LINE_WIDTH),
+    };
+
+    let mut diagram = String::new();
+    let mut number_line = scale.row();
+    scale.draw(&mut number_line, &(scale.low..=scale.high), '-', '-');
+    push_row(&mut diagram, "Number line", &number_line);
+    let (low, high) = (scale.low.to_string(), scale.high.to_string());
+    let padding = scale.width.saturating_sub(low.len() + high.len()).max(1);
+    writeln!(diagram, "{:LABEL_WIDTH$}{low}{:padding$}{high}", "", "")
+        .expect("writing to a String can't fail");
+
+    let mut row = scale.row();
+    for range in input.ranges() {
+        scale.draw(&mut row, range, '^', '^');
+    }
+    push_row(&mut diagram, "Input", &row);
+    let (mut sources, mut destinations) = (scale.row(), scale.row());
+    // Drawn last to first, as the first line to cover a number is the one that moves it
+    for (label, source, destination) in lines.iter().rev() {
+        scale.draw(&mut sources, source, '#', *label);
+        scale.draw(&mut destinations, destination, '*', *label);
+    }
+    push_row(&mut diagram, "Mapping source", &sources);
+    push_row(&mut diagram, "Mapping dest", &destinations);
+    for (label, piece) in &pieces {
+        let mut row = scale.row();
+        for range in piece.ranges() {
+            scale.draw(&mut row, range, '+', *label);
+        }
+        push_row(&mut diagram, &format!("  {label}]"), &row);
+    }
+    let mut row = scale.row();
+    for range in output.ranges() {
+        scale.draw(&mut row, range, '+', '+');
+    }
+    push_row(&mut diagram, "Output", &row);
+    diagram
+}
+
+/// Diagrams of each map from the `from` category to the `to` one applied in turn to `input`,
+/// each one titled with its map header
+pub fn render_chain(
+    almanac: &GardenAlmanac,
+    from: &str,
+    to: &str,
+    input: &RangeSet,
+    width: usize,
+) -> Result<String, PathError> {
+    let mut diagrams = Vec::new();
+    let mut ranges = input.clone();
+    for map in almanac.path(from, to)? {
+        diagrams.push(format!(
+            "{}-to-{} map:\n{}",
+            map.source,
+            map.destination,
+            render_mapping(&ranges, &map.mapping, width)
+        ));
+        ranges = GardenAlmanac::apply_mapping_to_ranges(&ranges, &map.mapping);
+    }
+    Ok(diagrams.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::{
+        generator,
+        tests::{mapping, EXAMPLE_INPUT},
+    };
+    use indoc::indoc;
+
+    #[test]
+    fn renders_the_doc_example() {
+        let mapping = mapping([[28, 30, 7], [50, 37, 4], [41, 44, 6]]);
+        // One column per number, from 25 to 53
+        let diagram = render_mapping(&RangeSet::from(25..=47), &mapping, LABEL_WIDTH + 29);
+        assert_eq!(
+            diagram,
+            indoc! {"
+                Number line    -> |---------------------------|
+                                  25                         53
+                Input          -> |^^^^^^^^^^^^^^^^^^^^^|
+                Mapping source ->      |##a##||b#|   |#c##|
+                Mapping dest   ->    |**a**|      |*c**|   |b*|
+                  a]           ->    |++a++|
+                  b]           ->                          |b+|
+                  c]           ->                 |c+|
+                  =]           -> |+=+|           |=|
+                Output         -> |++++++++|      |++|     |++|
+            "}
+        );
+    }
+
+    #[test]
+    fn renders_a_chain_of_maps() {
+        let (_, almanac) = generator(EXAMPLE_INPUT).expect("example should be a valid almanac");
+        let seeds = RangeSet::from(79..=92);
+        let chain = render_chain(&almanac, "seed", "location", &seeds, 60)
+            .expect("seeds should lead to locations");
+        let titles = chain.lines().filter(|line| line.ends_with(" map:"));
+        assert_eq!(titles.count(), 7);
+        assert!(chain.lines().all(|line| line.chars().count() <= 60));
+        let bounds = chain
+            .rsplit("map:\n")
+            .next()
+            .and_then(|diagram| diagram.lines().nth(1));
+        assert_eq!(
+            bounds.map(|line| line.split_whitespace().collect::<Vec<_>>()),
+            Some(vec!["46", "84"]),
+            "the last diagram should span the locations"
+        );
+        assert!(render_chain(&almanac, "location", "seed", &seeds, 60).is_err());
+    }
+}
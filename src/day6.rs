@@ -1,54 +1,213 @@
-use std::ops::RangeInclusive;
+use crate::{
+    parse::{self, Line, Location},
+    partition_point::PartitionPoint,
+    solution::{variants, Solution, Variant},
+};
+use boat_model::{BoatModel, Linear};
+use getset::CopyGetters;
+use std::{fmt, ops::RangeInclusive};
+
+pub mod boat_model;
 
 #[derive(Debug)]
 pub struct Races {
     race_records: Vec<RaceRecord>,
-    actual_race_record: RaceRecord,
+    /// Only part 2 reads the numbers this way, so only it fails if they don't make a number
+    actual_race_record: Result<RaceRecord, ParseError>,
 }
 
-#[derive(Debug)]
+impl Races {
+    pub fn race_records(&self) -> &[RaceRecord] {
+        &self.race_records
+    }
+
+    /// The single race read by ignoring the spaces between the numbers, unless they don't make a
+    /// number that fits in a `u64`
+    pub fn actual_race_record(&self) -> Result<&RaceRecord, ParseError> {
+        self.actual_race_record.as_ref().map_err(Clone::clone)
+    }
+}
+
+#[derive(Debug, CopyGetters)]
 pub struct RaceRecord {
+    #[getset(get_copy = "pub")]
     time: u64,
+    /// Farthest distance gone so far, which winning has to go past
+    #[getset(get_copy = "pub")]
     distance: u64,
 }
 
+/// How a race can be won, see [`RaceRecord::analyze`]
+#[derive(Debug, PartialEq, Eq)]
+pub struct RaceAnalysis {
+    /// Hold times that beat the record, if any does
+    pub winning_holds: Option<RangeInclusive<u64>>,
+    /// Hold time that goes the farthest, the shorter one of the two when they tie
+    pub optimal_hold: u64,
+    /// How far past the record the optimal hold time goes, negative if it falls short
+    pub margin: i128,
+}
+
+impl RaceAnalysis {
+    pub fn winning_ways(&self) -> u64 {
+        self.winning_holds
+            .as_ref()
+            .map_or(0, |holds| holds.end() - holds.start() + 1)
+    }
+}
+
+impl fmt::Display for RaceAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ways to win, holding for {}ms at best, which goes {}mm past the record",
+            self.winning_ways(),
+            self.optimal_hold,
+            self.margin
+        )
+    }
+}
+
 impl RaceRecord {
+    pub fn new(time: u64, distance: u64) -> Self {
+        Self { time, distance }
+    }
+
     fn record_broken(&self, time_held: u64) -> bool {
-        (self.time - time_held) * time_held > self.distance
-    }
-}
-
-#[allow(clippy::unwrap_used)]
-pub fn generator(input: &str) -> Races {
-    let mut lines: Vec<&str> = input.lines().collect();
-    let times = lines.remove(0).strip_prefix("Time:").unwrap();
-    let distances = lines.remove(0).strip_prefix("Distance:").unwrap();
-    let race_records = std::iter::zip(
-        times
-            .split_ascii_whitespace()
-            .map(|num| num.parse::<u64>().unwrap()),
-        distances
-            .split_ascii_whitespace()
-            .map(|num| num.parse::<u64>().unwrap()),
-    )
-    .map(|(time, distance)| RaceRecord { time, distance })
-    .collect();
-    let actual_race_record = RaceRecord {
-        time: times
-            .split_ascii_whitespace()
-            .collect::<String>()
-            .parse::<u64>()
-            .unwrap(),
-        distance: distances
-            .split_ascii_whitespace()
-            .collect::<String>()
-            .parse::<u64>()
-            .unwrap(),
-    };
-    Races {
+        Linear.distance(time_held, self.time) > u128::from(self.distance)
+    }
+
+    /// Number of hold times with which a boat going by `model` beats the record
+    pub fn winning_ways(&self, model: &(impl BoatModel + ?Sized)) -> u64 {
+        model
+            .winning_holds(self)
+            .map_or(0, |holds| holds.end() - holds.start() + 1)
+    }
+
+    /// Hold times that win, the optimal one & by how much it beats the record, without going
+    /// through the hold times
+    ///
+    /// # Panics
+    ///
+    /// Never, as the farthest a boat goes is a quarter of the square of the race time, which fits
+    /// in an `i128`
+    pub fn analyze(&self) -> RaceAnalysis {
+        let winning_holds = Linear.winning_holds(self);
+        let optimal_hold = self.time / 2;
+        let margin = i128::try_from(Linear.distance(optimal_hold, self.time))
+            .expect("distances are at most a quarter of the square of a u64")
+            - i128::from(self.distance);
+        RaceAnalysis {
+            winning_holds,
+            optimal_hold,
+            margin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    MissingPrefix {
+        location: Location,
+        expected: &'static str,
+    },
+    InvalidNumber {
+        location: Location,
+        found: String,
+    },
+    MismatchedRaceCount {
+        location: Location,
+        times: usize,
+        distances: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingPrefix { location, expected } => {
+                write!(
+                    f,
+                    "{location}: expected the line to start with `{expected}`"
+                )
+            }
+            ParseError::InvalidNumber { location, found } => {
+                write!(f, "{location}: expected a number, found `{found}`")
+            }
+            ParseError::MismatchedRaceCount {
+                location,
+                times,
+                distances,
+            } => write!(
+                f,
+                "{location}: expected a distance for each of the {times} times, found {distances}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn parse_number(line: &Line, location_of: &str, num_str: &str) -> Result<u64, ParseError> {
+    num_str.parse().map_err(|_| ParseError::InvalidNumber {
+        location: line.locate(location_of),
+        found: num_str.to_string(),
+    })
+}
+
+/// Parses the numbers on a line, along with the single number formed by ignoring spaces between
+/// them, which is only an error for part 2
+fn parse_record_line(
+    line: Option<Line>,
+    prefix: &'static str,
+    end_of_input: Location,
+) -> Result<(Vec<u64>, Result<u64, ParseError>), ParseError> {
+    let line = line.ok_or(ParseError::MissingPrefix {
+        location: end_of_input,
+        expected: prefix,
+    })?;
+    let numbers = line
+        .text
+        .strip_prefix(prefix)
+        .ok_or(ParseError::MissingPrefix {
+            location: line.start(),
+            expected: prefix,
+        })?;
+    let separate_numbers = numbers
+        .split_ascii_whitespace()
+        .map(|num| parse_number(&line, num, num))
+        .collect::<Result<_, _>>()?;
+    let actual_number = parse_number(
+        &line,
+        numbers.trim_start(),
+        &numbers.split_ascii_whitespace().collect::<String>(),
+    );
+    Ok((separate_numbers, actual_number))
+}
+
+pub fn generator(input: &str) -> Result<Races, ParseError> {
+    let mut lines = parse::lines(input);
+    let end_of_input = parse::end_of_input(input);
+    let (times, actual_time) = parse_record_line(lines.next(), "Time:", end_of_input)?;
+    let distances_line = lines.next();
+    let (distances, actual_distance) =
+        parse_record_line(distances_line, "Distance:", end_of_input)?;
+    if times.len() != distances.len() {
+        return Err(ParseError::MismatchedRaceCount {
+            location: distances_line.map_or(end_of_input, |line| line.end()),
+            times: times.len(),
+            distances: distances.len(),
+        });
+    }
+    let race_records = std::iter::zip(times, distances)
+        .map(|(time, distance)| RaceRecord::new(time, distance))
+        .collect();
+    let actual_race_record =
+        actual_time.and_then(|time| Ok(RaceRecord::new(time, actual_distance?)));
+    Ok(Races {
         race_records,
         actual_race_record,
-    }
+    })
 }
 
 pub fn part1(races: &Races) -> u64 {
@@ -59,12 +218,26 @@ pub fn part1_binary(races: &Races) -> u64 {
     races.race_records.iter().map(winning_ways_binary).product()
 }
 
-pub fn part2(races: &Races) -> u64 {
-    winning_ways(&races.actual_race_record)
+pub fn part2(races: &Races) -> Result<u64, ParseError> {
+    races.actual_race_record().map(winning_ways)
+}
+
+pub fn part2_binary(races: &Races) -> Result<u64, ParseError> {
+    races.actual_race_record().map(winning_ways_binary)
 }
 
-pub fn part2_binary(races: &Races) -> u64 {
-    winning_ways_binary(&races.actual_race_record)
+pub fn part1_closed_form(races: &Races) -> u64 {
+    races
+        .race_records
+        .iter()
+        .map(|race_record| race_record.analyze().winning_ways())
+        .product()
+}
+
+pub fn part2_closed_form(races: &Races) -> Result<u64, ParseError> {
+    races
+        .actual_race_record()
+        .map(|race_record| race_record.analyze().winning_ways())
 }
 
 fn winning_ways(race_record: &RaceRecord) -> u64 {
@@ -92,30 +265,20 @@ fn winning_ways_binary(race_record: &RaceRecord) -> u64 {
     max_holding_time - min_holding_time + 1
 }
 
-trait PartitionPoint<T> {
-    fn partition_point<P>(&self, pred: P) -> u64
-    where
-        P: FnMut(&T) -> bool;
-}
-
-impl PartitionPoint<u64> for RangeInclusive<u64> {
-    fn partition_point<P>(&self, mut pred: P) -> u64
-    where
-        P: FnMut(&u64) -> bool,
-    {
-        let mut start = *self.start();
-        let mut end = *self.end();
-        let start_state = pred(&start);
-        while start < end {
-            let mid = start + (end - start) / 2;
-            let cur_state = pred(&mid);
-            if cur_state == start_state {
-                start = mid + 1;
-            } else if cur_state != start_state {
-                end = mid;
-            }
-        }
-        start
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 6;
+    type Parsed = Races;
+    type Answer = u64;
+    type Error = ParseError;
+    const PARTS: [&'static [Variant<Self>]; 2] = [
+        variants![part1, part1_binary, part1_closed_form],
+        variants![fallible: part2, part2_binary, part2_closed_form],
+    ];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error> {
+        generator(input)
     }
 }
 
@@ -133,14 +296,125 @@ mod tests {
     #[test]
     fn part1_example() {
         // println!("{:?}", generator(EXAMPLE_INPUT));
-        assert_eq!(part1(&generator(EXAMPLE_INPUT)), 288);
-        assert_eq!(part1_binary(&generator(EXAMPLE_INPUT)), 288);
+        assert_eq!(generator(EXAMPLE_INPUT).map(|races| part1(&races)), Ok(288));
+        assert_eq!(
+            generator(EXAMPLE_INPUT).map(|races| part1_binary(&races)),
+            Ok(288)
+        );
+        assert_eq!(
+            generator(EXAMPLE_INPUT).map(|races| part1_closed_form(&races)),
+            Ok(288)
+        );
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&generator(EXAMPLE_INPUT)), 71503);
-        assert_eq!(part2_binary(&generator(EXAMPLE_INPUT)), 71503);
+        assert_eq!(
+            generator(EXAMPLE_INPUT).and_then(|races| part2(&races)),
+            Ok(71503)
+        );
+        assert_eq!(
+            generator(EXAMPLE_INPUT).and_then(|races| part2_binary(&races)),
+            Ok(71503)
+        );
+        assert_eq!(
+            generator(EXAMPLE_INPUT).and_then(|races| part2_closed_form(&races)),
+            Ok(71503)
+        );
+    }
+
+    #[test]
+    fn generator_errors() {
+        assert_eq!(
+            generator("Time:      7  15   30\n").err(),
+            Some(ParseError::MissingPrefix {
+                location: Location { line: 2, column: 1 },
+                expected: "Distance:",
+            })
+        );
+        assert_eq!(
+            generator("Time:      7  15   30\nDistance:  9  40").err(),
+            Some(ParseError::MismatchedRaceCount {
+                location: Location {
+                    line: 2,
+                    column: 17
+                },
+                times: 3,
+                distances: 2,
+            })
+        );
+        assert_eq!(
+            generator("Time:      7  1S   30\nDistance:  9  40  200").err(),
+            Some(ParseError::InvalidNumber {
+                location: Location {
+                    line: 1,
+                    column: 15
+                },
+                found: "1S".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn only_part2_reads_the_numbers_as_one() {
+        // 12 races, the spaces between which can't be ignored without going past `u64::MAX`
+        let races = generator(indoc! {"
+            Time:      7  15   30  7  15   30  7  15   30  7  15   30
+            Distance:  9  40  200  9  40  200  9  40  200  9  40  200
+        "})
+        .expect("each race should be valid");
+        assert_eq!(part1(&races), 288_u64.pow(4));
+        assert_eq!(
+            part2(&races),
+            Err(ParseError::InvalidNumber {
+                location: Location {
+                    line: 1,
+                    column: 12
+                },
+                found: "71530715307153071530".to_string(),
+            })
+        );
+
+        let races = generator("Time:\nDistance:\n").expect("no races is still a sheet");
+        assert_eq!(part1(&races), 1);
+        assert!(part2_closed_form(&races).is_err());
+    }
+
+    #[test]
+    fn closed_form_analysis() {
+        let analyze = |time, distance| RaceRecord::new(time, distance).analyze();
+        // Holding for 10ms or 20ms only ties the record
+        assert_eq!(
+            analyze(30, 200),
+            RaceAnalysis {
+                winning_holds: Some(11..=19),
+                optimal_hold: 15,
+                margin: 25,
+            }
+        );
+        assert_eq!(analyze(8, 16).winning_holds, None);
+        assert_eq!(analyze(8, 16).margin, 0);
+        assert_eq!(analyze(8, 15).winning_holds, Some(4..=4));
+        assert_eq!(analyze(5, 100).margin, -94);
+        assert_eq!(analyze(0, 0).winning_ways(), 0);
+
+        // Covering up to 2.5e19mm, past `u64::MAX`
+        let long_race = analyze(10_000_000_000, 18_000_000_000_000_000_000);
+        assert_eq!(long_race.winning_holds, Some(2_354_248_689..=7_645_751_311));
+        assert_eq!(long_race.margin, 7_000_000_000_000_000_000);
+        assert_eq!(analyze(u64::MAX, 0).winning_ways(), u64::MAX - 1);
+        assert_eq!(
+            analyze(8_000_000_000, 16_000_000_000_000_000_000).winning_ways(),
+            0
+        );
+        assert_eq!(
+            analyze(8_000_000_000, 15_999_999_999_999_999_999).winning_holds,
+            Some(4_000_000_000..=4_000_000_000)
+        );
+        assert_eq!(
+            analyze(7, 9).to_string(),
+            "4 ways to win, holding for 3ms at best, which goes 3mm past the record"
+        );
     }
 
     #[test]
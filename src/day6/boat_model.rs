@@ -0,0 +1,188 @@
+//! How far a boat goes depending on how long its button is held, for what-if variants of the races
+//!
+//! Every model has the boat go farther the longer the button is held, up to an optimal hold time,
+//! & then less far, as there's less time left to move. The hold times that beat a record are then
+//! a single range, found with a [`PartitionPoint`] search unless a model has a closed form for it.
+
+use super::RaceRecord;
+use crate::partition_point::PartitionPoint;
+use std::ops::RangeInclusive;
+
+pub trait BoatModel {
+    /// Distance covered in a race lasting `time` after holding the button for `time_held`, which
+    /// is at most `time`
+    fn distance(&self, time_held: u64, time: u64) -> u128;
+
+    /// Hold times that beat the record, if any does
+    fn winning_holds(&self, race_record: &RaceRecord) -> Option<RangeInclusive<u64>> {
+        search_winning_holds(self, race_record)
+    }
+}
+
+/// Each millisecond of holding adds one unit of speed, as in the puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Linear;
+
+/// The speed grows with the square of the hold time, the boat accelerating as it charges up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuadraticAcceleration;
+
+/// Like [`Linear`], but holding past `max_speed` milliseconds doesn't add any more speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CappedSpeed {
+    pub max_speed: u64,
+}
+
+impl BoatModel for Linear {
+    fn distance(&self, time_held: u64, time: u64) -> u128 {
+        u128::from(time - time_held) * u128::from(time_held)
+    }
+
+    /// Holding for `h` covers `h·(time - h)`, which beats the record when
+    /// `h² - time·h + distance < 0`, so strictly between the roots
+    /// `(time ± √(time² - 4·distance)) / 2`. A root that's an integer only ties the record.
+    fn winning_holds(&self, race_record: &RaceRecord) -> Option<RangeInclusive<u64>> {
+        let (time, record) = (race_record.time(), race_record.distance());
+        let discriminant =
+            (u128::from(time) * u128::from(time)).checked_sub(4 * u128::from(record))?;
+        // The square root being rounded down, this is at most one off the shortest hold time
+        let estimate = (u128::from(time) - discriminant.isqrt()) / 2;
+        let mut min_hold = u64::try_from(estimate).expect("the lower root is at most the time");
+        let beats = |time_held| self.distance(time_held, time) > u128::from(record);
+        while min_hold > 0 && beats(min_hold - 1) {
+            min_hold -= 1;
+        }
+        while min_hold <= time / 2 && !beats(min_hold) {
+            min_hold += 1;
+        }
+        // The distance is symmetric around half the time, so are the hold times that win
+        (min_hold <= time / 2).then(|| min_hold..=time - min_hold)
+    }
+}
+
+impl BoatModel for QuadraticAcceleration {
+    /// Saturates at `u128::MAX`, which only races longer than 2^42ms go past
+    fn distance(&self, time_held: u64, time: u64) -> u128 {
+        let speed = u128::from(time_held).saturating_mul(u128::from(time_held));
+        speed.saturating_mul(u128::from(time - time_held))
+    }
+}
+
+impl BoatModel for CappedSpeed {
+    fn distance(&self, time_held: u64, time: u64) -> u128 {
+        u128::from(time - time_held) * u128::from(time_held.min(self.max_speed))
+    }
+
+    /// Up to `max_speed`, the hold times that win are the [`Linear`] ones. Past it, holding for `h`
+    /// covers `max_speed·(time - h)`, which beats the record while `time - h` is more than
+    /// `distance / max_speed`.
+    fn winning_holds(&self, race_record: &RaceRecord) -> Option<RangeInclusive<u64>> {
+        let (time, record) = (race_record.time(), race_record.distance());
+        let up_to_cap = Linear
+            .winning_holds(race_record)
+            .filter(|holds| *holds.start() <= self.max_speed)
+            .map(|holds| *holds.start()..=(*holds.end()).min(self.max_speed));
+        let past_cap = record
+            .checked_div(self.max_speed)
+            .and_then(|time_left| time.checked_sub(time_left + 1))
+            .map(|last_hold| self.max_speed + 1..=last_hold)
+            .filter(|holds| !holds.is_empty());
+        match (up_to_cap, past_cap) {
+            // Holding for `max_speed` goes farther than holding any longer, so the ranges touch
+            (Some(up_to_cap), Some(past_cap)) => Some(*up_to_cap.start()..=*past_cap.end()),
+            (up_to_cap, past_cap) => up_to_cap.or(past_cap),
+        }
+    }
+}
+
+/// Hold times of `model` that beat the record, searching for the optimal hold time & then for
+/// where the distance crosses the record on either side of it
+pub fn search_winning_holds<M: BoatModel + ?Sized>(
+    model: &M,
+    race_record: &RaceRecord,
+) -> Option<RangeInclusive<u64>> {
+    let time = race_record.time();
+    let distance = |time_held| model.distance(time_held, time);
+    let beats = |time_held: &u64| distance(*time_held) > u128::from(race_record.distance());
+    let rising = |time_held: &u64| distance(time_held + 1) > distance(*time_held);
+    // Never rising means going the farthest right away, or never moving at all
+    let optimal_hold = if time > 0 && rising(&0) {
+        (0..time).partition_point(rising)
+    } else {
+        0
+    };
+    if !beats(&optimal_hold) {
+        return None;
+    }
+    let min_hold = if beats(&0) {
+        0
+    } else {
+        (0..=optimal_hold).partition_point(|time_held| !beats(time_held))
+    };
+    let max_hold = (optimal_hold..=time)
+        .checked_partition_point(beats)
+        .map_or(time, |first_losing| first_losing - 1);
+    Some(min_hold..=max_hold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hold times that win, going through each of them
+    fn brute_force(model: &dyn BoatModel, time: u64, distance: u64) -> Option<RangeInclusive<u64>> {
+        let winning = (0..=time)
+            .filter(|time_held| model.distance(*time_held, time) > u128::from(distance))
+            .collect::<Vec<_>>();
+        Some(*winning.first()?..=*winning.last()?)
+    }
+
+    #[test]
+    fn models_agree_with_brute_force() {
+        let models: [&dyn BoatModel; 5] = [
+            &Linear,
+            &QuadraticAcceleration,
+            &CappedSpeed { max_speed: 0 },
+            &CappedSpeed { max_speed: 4 },
+            &CappedSpeed { max_speed: 100 },
+        ];
+        for model in models {
+            for time in 0..30 {
+                for distance in (0..300).step_by(7).chain([9, 40, 200, 224, 225]) {
+                    let race_record = RaceRecord::new(time, distance);
+                    let expected = brute_force(model, time, distance);
+                    assert_eq!(
+                        model.winning_holds(&race_record),
+                        expected,
+                        "time {time}, distance {distance}"
+                    );
+                    assert_eq!(
+                        search_winning_holds(model, &race_record),
+                        expected,
+                        "time {time}, distance {distance}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn what_if_races() {
+        // The example races of the puzzle
+        let races =
+            [(7, 9), (15, 40), (30, 200)].map(|(time, distance)| RaceRecord::new(time, distance));
+        let ways = |model: &dyn BoatModel| -> Vec<u64> {
+            races
+                .iter()
+                .map(|race_record| race_record.winning_ways(model))
+                .collect()
+        };
+        assert_eq!(ways(&Linear), [4, 8, 9]);
+        assert_eq!(ways(&CappedSpeed { max_speed: 10 }), [4, 7, 0]);
+        assert_eq!(ways(&QuadraticAcceleration), [5, 13, 27]);
+        assert_eq!(
+            RaceRecord::new(71530, 940_200).winning_ways(&CappedSpeed { max_speed: 20 }),
+            24506
+        );
+    }
+}
@@ -1,185 +1,508 @@
+use crate::{
+    parse::{self, Line, Location},
+    solution::{variants, Solution, Variant},
+};
 use itertools::Itertools;
-use std::collections::HashSet;
-
-// const Cards: [char; 13] = [
-//     'A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2',
-// ];
-
-#[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
-enum Card {
-    Joker,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    Ten,
-    Jack,
-    Queen,
-    King,
-    Ace,
-}
-
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
-enum HandType {
-    HighCard,
-    OnePair,
-    TwoPair,
-    ThreeOfAKind,
-    FullHouse,
-    FourOfAKind,
-    FiveOfAKind,
-}
-
-type HandCards = [Card; 5];
-
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
-pub struct Hand {
-    hand_type: HandType,
-    cards: HandCards,
+use serde::{Serialize, Serializer};
+use std::{
+    cmp::{Ordering, Reverse},
+    fmt,
+};
+
+pub mod odds;
+pub mod poker;
+pub mod report;
+
+/// Strength of a card under some [`Rules`], which is its position in their alphabet
+pub type Rank = u8;
+
+pub const MIN_HAND_SIZE: usize = 3;
+pub const MAX_HAND_SIZE: usize = 7;
+
+/// The order in which cards are compared to break a tie between hands of the same type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Card by card from the first one, as in Camel Cards
+    FirstToLast,
+    LastToFirst,
+    /// Cards of larger groups first, stronger cards first among groups of the same size, as in
+    /// poker
+    GroupsFirst,
+}
+
+/// How hands are read & ranked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// Cards from the weakest to the strongest
+    alphabet: Vec<char>,
+    /// Cards that stand for whichever card makes the hand strongest
+    wildcards: Vec<Rank>,
+    hand_size: usize,
+    tie_break: TieBreak,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RulesError {
+    EmptyAlphabet,
+    InvalidCard { card: char },
+    DuplicateCard { card: char },
+    TooManyCards { count: usize },
+    UnknownWildcard { card: char },
+    UnsupportedHandSize { hand_size: usize },
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::EmptyAlphabet => write!(f, "expected at least one card"),
+            RulesError::InvalidCard { card } => {
+                write!(f, "expected a card that isn't whitespace, found {card:?}")
+            }
+            RulesError::DuplicateCard { card } => {
+                write!(f, "expected each card once, found `{card}` more than once")
+            }
+            RulesError::TooManyCards { count } => {
+                write!(f, "expected at most 256 cards, found {count}")
+            }
+            RulesError::UnknownWildcard { card } => {
+                write!(f, "expected wildcards to be cards, found `{card}`")
+            }
+            RulesError::UnsupportedHandSize { hand_size } => write!(
+                f,
+                "expected hands of {MIN_HAND_SIZE} to {MAX_HAND_SIZE} cards, found {hand_size}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+impl Rules {
+    /// Rules with the cards of `alphabet`, from the weakest to the strongest, among which
+    /// `wildcards` stand for whichever card makes a hand strongest
+    pub fn new(
+        alphabet: &str,
+        wildcards: &str,
+        hand_size: usize,
+        tie_break: TieBreak,
+    ) -> Result<Self, RulesError> {
+        let alphabet = alphabet.chars().collect::<Vec<_>>();
+        if alphabet.is_empty() {
+            return Err(RulesError::EmptyAlphabet);
+        }
+        if let Some(card) = alphabet.iter().find(|card| card.is_whitespace()) {
+            return Err(RulesError::InvalidCard { card: *card });
+        }
+        if let Some(card) = alphabet.iter().duplicates().next() {
+            return Err(RulesError::DuplicateCard { card: *card });
+        }
+        if alphabet.len() > usize::from(Rank::MAX) + 1 {
+            return Err(RulesError::TooManyCards {
+                count: alphabet.len(),
+            });
+        }
+        if !(MIN_HAND_SIZE..=MAX_HAND_SIZE).contains(&hand_size) {
+            return Err(RulesError::UnsupportedHandSize { hand_size });
+        }
+        let mut rules = Self {
+            alphabet,
+            wildcards: Vec::new(),
+            hand_size,
+            tie_break,
+        };
+        rules.wildcards = wildcards
+            .chars()
+            .map(|card| rules.rank(card).ok_or(RulesError::UnknownWildcard { card }))
+            .collect::<Result<_, _>>()?;
+        Ok(rules)
+    }
+
+    /// # Panics
+    ///
+    /// Never, as [`Rules::new`] only accepts alphabets with at most 256 cards
+    pub fn rank(&self, card: char) -> Option<Rank> {
+        let rank = self.alphabet.iter().position(|known| *known == card)?;
+        Some(Rank::try_from(rank).expect("alphabets have at most 256 cards"))
+    }
+
+    /// # Panics
+    ///
+    /// If `rank` is past the strongest card of the rules
+    pub fn card(&self, rank: Rank) -> char {
+        self.alphabet[usize::from(rank)]
+    }
+
+    /// # Panics
+    ///
+    /// Never, as [`Rules::new`] only accepts alphabets of 1 to 256 cards
+    pub fn strongest(&self) -> Rank {
+        Rank::try_from(self.alphabet.len() - 1).expect("alphabets have at most 256 cards")
+    }
+
+    pub fn is_wildcard(&self, rank: Rank) -> bool {
+        self.wildcards.contains(&rank)
+    }
+
+    pub fn hand_size(&self) -> usize {
+        self.hand_size
+    }
+
+    /// Cards from the strongest to the weakest, the way they're listed in error messages
+    fn strongest_first(&self) -> String {
+        self.alphabet.iter().rev().collect()
+    }
 }
 
+/// Which of the two puzzle parts' [`Rules`] to use, the only difference being what `J` stands for
 #[derive(Default)]
-struct CardsConfig {
-    j_is_joker: bool,
+pub struct CardsConfig {
+    pub j_is_joker: bool,
+}
+
+impl CardsConfig {
+    /// # Panics
+    ///
+    /// Never, as both alphabets of the puzzle make valid rules
+    pub fn rules(&self) -> Rules {
+        let (alphabet, wildcards) = if self.j_is_joker {
+            ("J23456789TQKA", "J")
+        } else {
+            ("23456789TJQKA", "")
+        };
+        Rules::new(alphabet, wildcards, 5, TieBreak::FirstToLast)
+            .expect("the puzzle's rules should be valid")
+    }
+}
+
+/// Sizes of the groups of cards of the same rank, largest first, wildcards joining the largest
+/// group. Comparing them group by group ranks hands the way the ladder of 5 card hands goes, from
+/// high card to five of a kind, for any hand size.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct HandType {
+    /// Padded with 0s
+    groups: [u8; MAX_HAND_SIZE],
+}
+
+impl HandType {
+    pub const HIGH_CARD: Self = Self::of(&[1, 1, 1, 1, 1]);
+    pub const ONE_PAIR: Self = Self::of(&[2, 1, 1, 1]);
+    pub const TWO_PAIR: Self = Self::of(&[2, 2, 1]);
+    pub const THREE_OF_A_KIND: Self = Self::of(&[3, 1, 1]);
+    pub const FULL_HOUSE: Self = Self::of(&[3, 2]);
+    pub const FOUR_OF_A_KIND: Self = Self::of(&[4, 1]);
+    pub const FIVE_OF_A_KIND: Self = Self::of(&[5]);
+
+    /// Hand type with the given group sizes, which should be sorted largest first
+    pub const fn of(groups: &[u8]) -> Self {
+        let mut padded = [0; MAX_HAND_SIZE];
+        let mut index = 0;
+        while index < groups.len() {
+            padded[index] = groups[index];
+            index += 1;
+        }
+        Self { groups: padded }
+    }
+
+    /// Hand type of a hand with the given number of cards of each rank other than wildcards,
+    /// along with `wildcards` cards, which join the largest group as that's what makes the hand
+    /// strongest
+    ///
+    /// # Panics
+    ///
+    /// If the counts & wildcards add up to more cards than a group can hold, which hands of at
+    /// most [`MAX_HAND_SIZE`] cards never do
+    pub fn from_counts(counts: impl IntoIterator<Item = usize>, wildcards: usize) -> Self {
+        let mut groups = [0; MAX_HAND_SIZE];
+        let sorted = counts.into_iter().sorted_unstable_by(|a, b| b.cmp(a));
+        for (group, count) in groups.iter_mut().zip(sorted) {
+            *group = u8::try_from(count).expect("hands have at most 7 cards");
+        }
+        groups[0] += u8::try_from(wildcards).expect("hands have at most 7 cards");
+        Self { groups }
+    }
+
+    /// Group sizes, largest first
+    pub fn groups(&self) -> &[u8] {
+        let len = self.groups.iter().take_while(|group| **group > 0).count();
+        &self.groups[..len]
+    }
+}
+
+impl Serialize for HandType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const COUNTS: [&str; MAX_HAND_SIZE + 1] = [
+            "zero", "one", "two", "three", "four", "five", "six", "seven",
+        ];
+        match self.groups() {
+            [] => write!(f, "no cards"),
+            [1, ..] => write!(f, "high card"),
+            [3, 2] => write!(f, "full house"),
+            [2, 2, rest @ ..] if rest.iter().all(|group| *group == 1) => write!(f, "two pair"),
+            [2, rest @ ..] if rest.iter().all(|group| *group == 1) => write!(f, "one pair"),
+            [largest, rest @ ..] if rest.iter().all(|group| *group == 1) => {
+                write!(f, "{} of a kind", COUNTS[usize::from(*largest)])
+            }
+            groups => write!(f, "{}", groups.iter().join("+")),
+        }
+    }
+}
+
+/// Hands rank by type, then by their cards in the order the rules break ties in
+#[derive(Debug, Clone)]
+pub struct Hand {
+    kind: HandType,
+    /// In the order they were dealt
+    cards: Vec<Rank>,
+    /// Positions of the cards in the order the rules break ties in
+    tie_break_order: Vec<usize>,
+    /// Card the wildcards stand for, if there are any
+    substitute: Option<Rank>,
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.kind
+            .cmp(&other.kind)
+            .then_with(|| self.tie_breakers().cmp(other.tie_breakers()))
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Hand {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    UnknownCard {
+        location: Location,
+        found: char,
+        expected: String,
+    },
+    WrongHandSize {
+        location: Location,
+        found: usize,
+        expected: usize,
+    },
+    MissingBid {
+        location: Location,
+    },
+    InvalidNumber {
+        location: Location,
+        found: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnknownCard {
+                location,
+                found,
+                expected,
+            } => {
+                write!(f, "{location}: expected one of {expected}, found `{found}`")
+            }
+            ParseError::WrongHandSize {
+                location,
+                found,
+                expected,
+            } => {
+                write!(
+                    f,
+                    "{location}: expected a hand of {expected} cards, found {found}"
+                )
+            }
+            ParseError::MissingBid { location } => {
+                write!(f, "{location}: expected a bid after the hand")
+            }
+            ParseError::InvalidNumber { location, found } => {
+                write!(f, "{location}: expected a number, found `{found}`")
+            }
+        }
+    }
 }
 
+impl std::error::Error for ParseError {}
+
 impl Hand {
-    fn from_card_str(cards: &str, config: &CardsConfig) -> Self {
-        assert!(cards.len() == 5);
-        if let Some(cards) = cards
-            .chars()
-            .map(|char| match char {
-                'A' => Card::Ace,
-                'K' => Card::King,
-                'Q' => Card::Queen,
-                'J' => {
-                    if config.j_is_joker {
-                        Card::Joker
-                    } else {
-                        Card::Jack
-                    }
-                }
-                'T' => Card::Ten,
-                '9' => Card::Nine,
-                '8' => Card::Eight,
-                '7' => Card::Seven,
-                '6' => Card::Six,
-                '5' => Card::Five,
-                '4' => Card::Four,
-                '3' => Card::Three,
-                '2' => Card::Two,
-                _ => unreachable!(),
+    /// Parses `cards`, which should be a slice of `line` so that errors can point into it
+    pub fn from_card_str(line: &Line, cards: &str, rules: &Rules) -> Result<Self, ParseError> {
+        let hand_cards = cards
+            .char_indices()
+            .map(|(offset, card)| {
+                rules.rank(card).ok_or_else(|| ParseError::UnknownCard {
+                    location: line.locate(&cards[offset..]),
+                    found: card,
+                    expected: rules.strongest_first(),
+                })
             })
-            .collect_tuple::<(Card, Card, Card, Card, Card)>()
-        {
-            Hand::from_cards([cards.0, cards.1, cards.2, cards.3, cards.4])
-        } else {
-            unreachable!()
-        }
-    }
-
-    fn from_cards(cards: HandCards) -> Self {
-        let counts = cards.iter().counts();
-        let distinct_cards = counts.len();
-        let count_values = counts.values().collect::<HashSet<_>>();
-        let hand_type = match distinct_cards {
-            1 => HandType::FiveOfAKind,
-            2 if count_values.contains(&4) => HandType::FourOfAKind,
-            2 if count_values.contains(&3) => HandType::FullHouse,
-            3 if count_values.contains(&3) => HandType::ThreeOfAKind,
-            3 if count_values.contains(&2) => HandType::TwoPair,
-            4 => HandType::OnePair,
-            5 => HandType::HighCard,
-            _ => unreachable!(),
+            .collect::<Result<Vec<_>, _>>()?;
+        if hand_cards.len() != rules.hand_size {
+            return Err(ParseError::WrongHandSize {
+                location: line.locate(cards),
+                found: hand_cards.len(),
+                expected: rules.hand_size,
+            });
+        }
+        Ok(Hand::from_cards(hand_cards, rules))
+    }
+
+    /// Hand of the cards of the given ranks, in the order they were dealt
+    pub fn from_cards(cards: Vec<Rank>, rules: &Rules) -> Self {
+        let counts = cards
+            .iter()
+            .filter(|card| !rules.is_wildcard(**card))
+            .counts();
+        let wildcards = cards.len() - counts.values().sum::<usize>();
+        // Joining the largest group, the strongest one among those of the same size, or the
+        // strongest card if there's only wildcards
+        let substitute = (wildcards > 0)
+            .then(|| {
+                let largest_group = counts.iter().max_by_key(|(card, count)| (**count, **card));
+                largest_group.map(|(card, _)| **card).or_else(|| {
+                    (0..=rules.strongest())
+                        .rev()
+                        .find(|card| !rules.is_wildcard(*card))
+                })
+            })
+            .flatten();
+        let hand_type = HandType::from_counts(counts.into_values(), wildcards);
+        let tie_break_order = match rules.tie_break {
+            TieBreak::FirstToLast => (0..cards.len()).collect(),
+            TieBreak::LastToFirst => (0..cards.len()).rev().collect(),
+            TieBreak::GroupsFirst => {
+                let counts = cards.iter().counts();
+                (0..cards.len())
+                    .sorted_by_key(|position| {
+                        let card = &cards[*position];
+                        (Reverse(counts[card]), Reverse(*card))
+                    })
+                    .collect()
+            }
         };
-        if let Some(joker_count) = counts.get(&Card::Joker) {
-            let hand_type = hand_type_with_jokers(&hand_type, *joker_count);
-            Hand { hand_type, cards }
-        } else {
-            Hand { hand_type, cards }
-        }
-    }
-}
-
-#[allow(clippy::match_same_arms)]
-fn hand_type_with_jokers(old_hand_type: &HandType, joker_count: usize) -> HandType {
-    match old_hand_type {
-        HandType::HighCard => match joker_count {
-            1 => HandType::OnePair, // Include any other card
-            _ => unreachable!(),    // At max 1 of each card
-        },
-        HandType::OnePair => match joker_count {
-            1 => HandType::ThreeOfAKind, // Include the existing pair
-            2 => HandType::ThreeOfAKind, // Joker pair => Include any other card
-            _ => unreachable!(),         // At max 2 of each card
-        },
-        HandType::TwoPair => match joker_count {
-            1 => HandType::FullHouse, // Include one of the existing pairs to get triplet & pair
-            2 => HandType::FourOfAKind, // Joker pair => Include other pair
-            _ => unreachable!(),      // At max 2 of each card
-        },
-        HandType::ThreeOfAKind => match joker_count {
-            1 => HandType::FourOfAKind, // Include the existing triplet
-            3 => HandType::FourOfAKind, // Joker triplet => Include any other card
-            _ => unreachable!(),        // At max 3 of each card & Joker pair will make FullHouse
-        },
-        HandType::FullHouse => match joker_count {
-            2 => HandType::FiveOfAKind, // Joker pair => Include the triplet
-            3 => HandType::FiveOfAKind, // Joker triplet => Include the pair
-            _ => unreachable!(),        // At max 3 of each card, and 1 Joker will make ThreeOfAKind
-        },
-        HandType::FourOfAKind => match joker_count {
-            1 => HandType::FiveOfAKind, // Include the Quadruplet
-            4 => HandType::FiveOfAKind, // Joker Quadruplet => Include the fifth card
-            _ => unreachable!(), // At max 4 of each card, and 2/3 Jokers would no longer be FourOfAKind
-        },
-        HandType::FiveOfAKind => HandType::FiveOfAKind, // What more you want?
-    }
-}
-
-#[allow(clippy::unwrap_used)]
-fn parse_hands(input: &str, cards_config: &CardsConfig) -> Vec<(Hand, u32)> {
-    input
-        .lines()
+        Hand {
+            kind: hand_type,
+            cards,
+            tie_break_order,
+            substitute,
+        }
+    }
+
+    pub fn hand_type(&self) -> HandType {
+        self.kind
+    }
+
+    pub fn cards(&self) -> &[Rank] {
+        &self.cards
+    }
+
+    pub fn substitute(&self) -> Option<Rank> {
+        self.substitute
+    }
+
+    fn tie_breakers(&self) -> impl Iterator<Item = Rank> + '_ {
+        self.tie_break_order
+            .iter()
+            .map(|position| self.cards[*position])
+    }
+
+    /// Position of the first card that tells this hand & `other` apart when they're of the same
+    /// type, if they are & have different cards
+    pub fn tie_break_position(&self, other: &Hand) -> Option<usize> {
+        if self.kind != other.kind {
+            return None;
+        }
+        self.tie_break_order
+            .iter()
+            .zip(&other.tie_break_order)
+            .find(|(position, other_position)| {
+                self.cards[**position] != other.cards[**other_position]
+            })
+            .map(|(position, _)| *position)
+    }
+}
+
+fn parse_hands(input: &str, rules: &Rules) -> Result<Vec<(Hand, u32)>, ParseError> {
+    parse::lines(input)
         .map(|line| {
-            let (cards_str, bid) = line.split_once(' ').unwrap();
-            (
-                Hand::from_card_str(cards_str, cards_config),
-                bid.parse::<u32>().unwrap(),
-            )
+            let (cards_str, bid) =
+                line.text
+                    .split_once(' ')
+                    .ok_or_else(|| ParseError::MissingBid {
+                        location: line.end(),
+                    })?;
+            let bid = bid.parse::<u32>().map_err(|_| ParseError::InvalidNumber {
+                location: line.locate(bid),
+                found: bid.to_string(),
+            })?;
+            Ok((Hand::from_card_str(&line, cards_str, rules)?, bid))
         })
         .collect()
 }
 
-fn calculate_bid(input: &str, cards_config: &CardsConfig) -> u32 {
-    parse_hands(input, cards_config)
+/// Hands of `input` with their bids, from the weakest to the strongest under `rules`
+pub fn ranked_hands(input: &str, rules: &Rules) -> Result<Vec<(Hand, u32)>, ParseError> {
+    let mut hands = parse_hands(input, rules)?;
+    hands.sort_by(|(hand_a, _), (hand_b, _)| hand_a.cmp(hand_b));
+    Ok(hands)
+}
+
+/// Total winnings of the hands of `input` under `rules`, which [`report::ranking`] breaks down
+pub fn calculate_bid(input: &str, rules: &Rules) -> Result<u32, ParseError> {
+    Ok(ranked_hands(input, rules)?
         .iter()
-        .sorted_by(|(hand_a, _), (hand_b, _)| Hand::cmp(hand_a, hand_b))
         .enumerate()
-        // .inspect(|(i, (hand, bid))| println!("{} {hand:?} {bid}", i + 1))
         .map(|(i, (_, bid))| bid * (i as u32 + 1))
-        .sum()
+        .sum())
 }
 
-pub fn part1(input: &str) -> u32 {
-    calculate_bid(input, &CardsConfig::default())
+pub fn part1(input: &str) -> Result<u32, ParseError> {
+    calculate_bid(input, &CardsConfig::default().rules())
 }
 
-pub fn part2(input: &str) -> u32 {
-    calculate_bid(input, &CardsConfig { j_is_joker: true })
+pub fn part2(input: &str) -> Result<u32, ParseError> {
+    calculate_bid(input, &CardsConfig { j_is_joker: true }.rules())
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 7;
+    type Parsed = String;
+    type Answer = u32;
+    type Error = ParseError;
+    const PARTS: [&'static [Variant<Self>]; 2] =
+        [variants![fallible: part1], variants![fallible: part2]];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error> {
+        // Parts parse hands themselves, as what `J` stands for differs between them
+        Ok(input.to_string())
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(super) mod tests {
 
     use super::*;
     use indoc::indoc;
 
-    const EXAMPLE_INPUT: &str = indoc! {"
+    pub(super) const EXAMPLE_INPUT: &str = indoc! {"
         32T3K 765
         T55J5 684
         KK677 28
@@ -187,26 +510,246 @@ mod tests {
         QQQJA 483
     "};
 
+    fn hand(cards: &str) -> Hand {
+        hand_with(cards, &CardsConfig::default().rules())
+    }
+
+    fn hand_with(cards: &str, rules: &Rules) -> Hand {
+        Hand::from_card_str(&Line::new(0, cards), cards, rules).expect("test hands should be valid")
+    }
+
+    /// How jokers upgrade the type a 5 card hand has when they're counted as regular cards, as
+    /// worked out by hand before hand types were derived from counts
+    fn hand_type_with_jokers(old_hand_type: HandType, joker_count: usize) -> HandType {
+        match (old_hand_type, joker_count) {
+            (hand_type, 0) => hand_type,
+            (HandType::HIGH_CARD, 1) => HandType::ONE_PAIR, // Include any other card
+            (HandType::ONE_PAIR, 1 | 2) => HandType::THREE_OF_A_KIND, // Include the pair
+            (HandType::TWO_PAIR, 1) => HandType::FULL_HOUSE, // Include one of the pairs
+            // Include the other pair, or the triplet
+            (HandType::TWO_PAIR, 2) | (HandType::THREE_OF_A_KIND, 1 | 3) => {
+                HandType::FOUR_OF_A_KIND
+            }
+            (HandType::FULL_HOUSE | HandType::FOUR_OF_A_KIND | HandType::FIVE_OF_A_KIND, _) => {
+                HandType::FIVE_OF_A_KIND // Include the other group
+            }
+            _ => unreachable!("at most 5 cards, the jokers being one of the groups"),
+        }
+    }
+
     #[test]
     fn hand_ordering() {
-        let five_of_a_kind = Hand::from_card_str("AAAAA", &CardsConfig::default());
-        let four_of_a_kind = Hand::from_card_str("33332", &CardsConfig::default());
-        let four_of_a_kind_other = Hand::from_card_str("2AAAA", &CardsConfig::default());
-        let three_of_a_kind = Hand::from_card_str("T55J5", &CardsConfig::default());
-        let two_pair = Hand::from_card_str("KTJJT", &CardsConfig::default());
+        let five_of_a_kind = hand("AAAAA");
+        let four_of_a_kind = hand("33332");
+        let four_of_a_kind_other = hand("2AAAA");
+        let three_of_a_kind = hand("T55J5");
+        let two_pair = hand("KTJJT");
         assert!(five_of_a_kind > four_of_a_kind);
         assert!(five_of_a_kind > four_of_a_kind_other);
         assert!(four_of_a_kind > four_of_a_kind_other);
         assert!(three_of_a_kind > two_pair);
     }
 
+    #[test]
+    fn hand_types_from_counts() {
+        let rules = |hand_size| {
+            Rules::new("23456789TJQKA", "", hand_size, TieBreak::FirstToLast)
+                .expect("rules should be valid")
+        };
+        let hand_type = |cards: &str| hand_with(cards, &rules(cards.len())).hand_type();
+        assert_eq!(hand_type("23456"), HandType::HIGH_CARD);
+        assert_eq!(hand_type("A23A4"), HandType::ONE_PAIR);
+        assert_eq!(hand_type("23432"), HandType::TWO_PAIR);
+        assert_eq!(hand_type("TTT98"), HandType::THREE_OF_A_KIND);
+        assert_eq!(hand_type("23332"), HandType::FULL_HOUSE);
+        assert_eq!(hand_type("AA8AA"), HandType::FOUR_OF_A_KIND);
+        assert_eq!(hand_type("AAAAA"), HandType::FIVE_OF_A_KIND);
+        assert_eq!(hand_type("KKK").to_string(), "three of a kind");
+        assert_eq!(hand_type("KK2").to_string(), "one pair");
+        assert_eq!(hand_type("2223334").to_string(), "3+3+1");
+        assert_eq!(hand_type("2222223").to_string(), "six of a kind");
+        assert!(hand_type("2222333") > hand_type("2222345"));
+        assert!(hand_type("2233445") > hand_type("2233456"));
+        assert!(hand_type("AKQ") < hand_type("223"));
+    }
+
+    #[test]
+    fn wildcards_and_tie_breaks() {
+        let jokers = CardsConfig { j_is_joker: true }.rules();
+        assert!(hand_with("JKKK2", &jokers) < hand_with("QQQQ2", &jokers));
+
+        let wild_twos = Rules::new("23456789TJQKA", "23", 7, TieBreak::GroupsFirst)
+            .expect("rules should be valid");
+        assert_eq!(
+            hand_with("A2K3QJT", &wild_twos).hand_type(),
+            HandType::of(&[3, 1, 1, 1, 1])
+        );
+        assert!(hand_with("KK99AA5", &wild_twos) > hand_with("AA99KK4", &wild_twos));
+        assert!(hand_with("99TTAAK", &wild_twos) < hand_with("34567K2", &wild_twos));
+
+        let last_first = Rules::new("23456789TJQKA", "", 3, TieBreak::LastToFirst)
+            .expect("rules should be valid");
+        assert!(hand_with("29A", &last_first) > hand_with("A9K", &last_first));
+    }
+
+    /// Strongest hand type the wildcards can make, trying every card other than a wildcard for each
+    /// of them
+    fn best_substitution(cards: &[Rank], rules: &Rules) -> HandType {
+        let mut counts = vec![0; rules.alphabet.len()];
+        let mut wildcards = 0;
+        for card in cards {
+            if rules.is_wildcard(*card) {
+                wildcards += 1;
+            } else {
+                counts[usize::from(*card)] += 1;
+            }
+        }
+        // Cards that aren't in the hand all make the same hand types, so one of them stands for all
+        let absent =
+            (0..counts.len()).find(|card| counts[*card] == 0 && !rules.is_wildcard(*card as Rank));
+        (0..counts.len())
+            .filter(|card| counts[*card] > 0)
+            .chain(absent)
+            .combinations_with_replacement(wildcards)
+            .map(|substitutes| {
+                let mut counts = counts.clone();
+                for card in substitutes {
+                    counts[card] += 1;
+                }
+                HandType::from_counts(counts.into_iter().filter(|count| *count > 0), 0)
+            })
+            .max()
+            // Only wildcards, none of which can stand for anything else
+            .unwrap_or_else(|| HandType::from_counts([], wildcards))
+    }
+
+    /// Every hand that can be dealt under `rules`, as ranks
+    fn all_hands(rules: &Rules) -> impl Iterator<Item = Vec<Rank>> {
+        let (alphabet_len, hand_size) = (rules.alphabet.len(), rules.hand_size());
+        // Each hand's cards are the digits of its index in base `alphabet_len`
+        (0..alphabet_len.pow(hand_size as u32)).map(move |mut index| {
+            (0..hand_size)
+                .map(|_| {
+                    let card = index % alphabet_len;
+                    index /= alphabet_len;
+                    card as Rank
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn jokers_match_the_table_for_every_hand() {
+        let regular = CardsConfig::default().rules();
+        let jokers = CardsConfig { j_is_joker: true }.rules();
+        let as_regular = jokers
+            .alphabet
+            .iter()
+            .map(|card| regular.rank(*card).expect("both have the same cards"))
+            .collect::<Vec<_>>();
+        let mut hands = 0;
+        for cards in all_hands(&jokers) {
+            let hand_type = Hand::from_cards(cards.clone(), &jokers).hand_type();
+            let joker_count = cards
+                .iter()
+                .filter(|card| jokers.is_wildcard(**card))
+                .count();
+            let as_regular_cards = cards.iter().map(|card| as_regular[usize::from(*card)]);
+            let regular_hand_type =
+                Hand::from_cards(as_regular_cards.collect(), &regular).hand_type();
+            assert_eq!(
+                hand_type,
+                hand_type_with_jokers(regular_hand_type, joker_count),
+                "{cards:?}"
+            );
+            assert_eq!(hand_type, best_substitution(&cards, &jokers), "{cards:?}");
+            hands += 1;
+        }
+        assert_eq!(hands, 13_usize.pow(5));
+    }
+
+    #[test]
+    fn wildcards_make_the_best_hand_type() {
+        for rules in [
+            Rules::new("23456", "23", 6, TieBreak::FirstToLast),
+            Rules::new("2345", "5", 7, TieBreak::GroupsFirst),
+            Rules::new("23456789", "", 3, TieBreak::LastToFirst),
+            Rules::new("23", "23", 4, TieBreak::FirstToLast),
+        ] {
+            let rules = rules.expect("rules should be valid");
+            for cards in all_hands(&rules) {
+                assert_eq!(
+                    Hand::from_cards(cards.clone(), &rules).hand_type(),
+                    best_substitution(&cards, &rules),
+                    "{cards:?} under {rules:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert_eq!(
+            Rules::new("", "", 5, TieBreak::FirstToLast),
+            Err(RulesError::EmptyAlphabet)
+        );
+        assert_eq!(
+            Rules::new("23 4", "", 5, TieBreak::FirstToLast),
+            Err(RulesError::InvalidCard { card: ' ' })
+        );
+        assert_eq!(
+            Rules::new("2342", "", 5, TieBreak::FirstToLast),
+            Err(RulesError::DuplicateCard { card: '2' })
+        );
+        assert_eq!(
+            Rules::new("234", "5", 5, TieBreak::FirstToLast),
+            Err(RulesError::UnknownWildcard { card: '5' })
+        );
+        assert_eq!(
+            Rules::new("234", "", 8, TieBreak::FirstToLast),
+            Err(RulesError::UnsupportedHandSize { hand_size: 8 })
+        );
+    }
+
     #[test]
     fn part1_example() {
-        assert_eq!(part1(EXAMPLE_INPUT), 6440);
+        assert_eq!(part1(EXAMPLE_INPUT), Ok(6440));
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(EXAMPLE_INPUT), 5905);
+        assert_eq!(part2(EXAMPLE_INPUT), Ok(5905));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            part1("32T3K 765\nT55X5 684"),
+            Err(ParseError::UnknownCard {
+                location: Location { line: 2, column: 4 },
+                found: 'X',
+                expected: "AKQJT98765432".to_string(),
+            })
+        );
+        assert_eq!(
+            part2("32T3 765"),
+            Err(ParseError::WrongHandSize {
+                location: Location { line: 1, column: 1 },
+                found: 4,
+                expected: 5,
+            })
+        );
+        assert_eq!(
+            part1("32T3K"),
+            Err(ParseError::MissingBid {
+                location: Location { line: 1, column: 6 },
+            })
+        );
+        assert_eq!(
+            part2("32T3K 765\nT55X5 684")
+                .expect_err("X isn't a card")
+                .to_string(),
+            "line 2, column 4: expected one of AKQT98765432J, found `X`"
+        );
     }
 }
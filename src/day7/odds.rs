@@ -0,0 +1,407 @@
+//! How likely a partial hand is to end up as each [`HandType`] once the rest of its cards are drawn
+//! from a deck
+//!
+//! The odds are exact when there are few enough ways to draw the rest of the cards to go through
+//! them all, and otherwise estimated from seeded random draws, so that the same estimate comes out
+//! every time. Either way, hand types come from [`Hand::from_cards`] under the given [`Rules`],
+//! wildcards included.
+
+use super::{Hand, HandType, Rank, Rules};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{collections::BTreeMap, fmt};
+
+/// Cards left to draw from, as the number of copies of each card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deck {
+    /// Indexed by rank
+    counts: Vec<usize>,
+}
+
+impl Deck {
+    /// `copies` of every card of `rules`
+    pub fn uniform(rules: &Rules, copies: usize) -> Self {
+        Self {
+            counts: vec![copies; usize::from(rules.strongest()) + 1],
+        }
+    }
+
+    /// Deck of the cards written in `cards`, each character being one card, unless one isn't a
+    /// card of `rules`
+    pub fn from_cards(rules: &Rules, cards: &str) -> Option<Self> {
+        let mut deck = Self::uniform(rules, 0);
+        for rank in ranks(rules, cards)? {
+            deck.counts[usize::from(rank)] += 1;
+        }
+        Some(deck)
+    }
+
+    /// The deck without `cards`, leaving none of a card there aren't enough copies of, or that
+    /// isn't in the deck at all
+    #[must_use]
+    pub fn without(mut self, cards: &[Rank]) -> Self {
+        for card in cards {
+            if let Some(count) = self.counts.get_mut(usize::from(*card)) {
+                *count = count.saturating_sub(1);
+            }
+        }
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Ranks of the cards written in `cards` under `rules`, unless one isn't a card of theirs
+pub fn ranks(rules: &Rules, cards: &str) -> Option<Vec<Rank>> {
+    cards.chars().map(|card| rules.rank(card)).collect()
+}
+
+/// When to enumerate draws rather than sample them, & how to sample them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sampling {
+    /// Most distinct draws to go through for exact odds, which is the number of ways to pick the
+    /// missing cards among the different cards of the deck, ignoring their order
+    pub exact_limit: u128,
+    /// Random draws to estimate the odds from, which can't be 0
+    pub samples: usize,
+    pub seed: u64,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self {
+            exact_limit: 100_000,
+            samples: 100_000,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Odds {
+    /// Probability of each hand type the hand can end up as, leaving out those it can't
+    pub probabilities: BTreeMap<HandType, f64>,
+    /// Whether the probabilities are exact, rather than estimated from random draws
+    pub exact: bool,
+}
+
+impl Odds {
+    pub fn probability(&self, hand_type: HandType) -> f64 {
+        self.probabilities.get(&hand_type).copied().unwrap_or(0.0)
+    }
+
+    /// Probability of ending up as `hand_type` or better
+    pub fn at_least(&self, hand_type: HandType) -> f64 {
+        self.probabilities.range(hand_type..).map(|(_, p)| p).sum()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OddsError {
+    TooManyCards {
+        known: usize,
+        hand_size: usize,
+    },
+    DeckTooSmall {
+        missing: usize,
+        left: usize,
+    },
+    /// A known card or a card of the deck that isn't one of the rules
+    UnknownCard {
+        rank: Rank,
+    },
+    /// The odds would have to be estimated from 0 random draws
+    NoSamples,
+}
+
+impl fmt::Display for OddsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OddsError::TooManyCards { known, hand_size } => {
+                write!(f, "expected at most {hand_size} known cards, found {known}")
+            }
+            OddsError::DeckTooSmall { missing, left } => write!(
+                f,
+                "expected at least {missing} cards left in the deck, found {left}"
+            ),
+            OddsError::UnknownCard { rank } => {
+                write!(f, "expected cards of the rules, found rank {rank}")
+            }
+            OddsError::NoSamples => {
+                write!(
+                    f,
+                    "expected at least one random draw to estimate the odds from"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for OddsError {}
+
+/// Odds of the hand types that the `known` cards end up as, once the rest of the hand is drawn
+/// from `deck`, which shouldn't hold the known cards anymore
+pub fn odds(
+    known: &[Rank],
+    deck: &Deck,
+    rules: &Rules,
+    sampling: Sampling,
+) -> Result<Odds, OddsError> {
+    let missing = rules
+        .hand_size()
+        .checked_sub(known.len())
+        .ok_or(OddsError::TooManyCards {
+            known: known.len(),
+            hand_size: rules.hand_size(),
+        })?;
+    let in_deck = (0..=Rank::MAX)
+        .zip(&deck.counts)
+        .filter(|(_, count)| **count > 0)
+        .map(|(rank, _)| rank);
+    if let Some(rank) = known
+        .iter()
+        .copied()
+        .chain(in_deck)
+        .find(|rank| *rank > rules.strongest())
+    {
+        return Err(OddsError::UnknownCard { rank });
+    }
+    if deck.len() < missing {
+        return Err(OddsError::DeckTooSmall {
+            missing,
+            left: deck.len(),
+        });
+    }
+    let kinds = deck.counts.iter().filter(|count| **count > 0).count();
+    // Ways to pick `missing` cards among `kinds`, repeats allowed
+    let draws = binomial((kinds + missing).saturating_sub(1), missing);
+    if draws <= sampling.exact_limit {
+        Ok(exact_odds(known, deck, rules, missing))
+    } else if sampling.samples == 0 {
+        Err(OddsError::NoSamples)
+    } else {
+        Ok(sampled_odds(known, deck, rules, missing, sampling))
+    }
+}
+
+fn binomial(n: usize, k: usize) -> u128 {
+    (0..k).fold(1, |result, i| result * (n - i) as u128 / (i as u128 + 1))
+}
+
+/// Goes through every combination of counts of each card drawn, weighing each by the number of
+/// ways to draw it
+fn exact_odds(known: &[Rank], deck: &Deck, rules: &Rules, missing: usize) -> Odds {
+    fn draw(
+        rank: usize,
+        missing: usize,
+        ways: u128,
+        hand: &mut Vec<Rank>,
+        context: (&Deck, &Rules, &mut BTreeMap<HandType, u128>),
+    ) {
+        let (deck, rules, weights) = context;
+        if missing == 0 {
+            let hand_type = Hand::from_cards(hand.clone(), rules).hand_type();
+            *weights.entry(hand_type).or_default() += ways;
+            return;
+        }
+        if rank == deck.counts.len() {
+            return;
+        }
+        for count in 0..=deck.counts[rank].min(missing) {
+            let card = Rank::try_from(rank).expect("alphabets have at most 256 cards");
+            hand.extend(std::iter::repeat_n(card, count));
+            let ways = ways * binomial(deck.counts[rank], count);
+            draw(
+                rank + 1,
+                missing - count,
+                ways,
+                hand,
+                (deck, rules, &mut *weights),
+            );
+            hand.truncate(hand.len() - count);
+        }
+    }
+
+    let mut weights = BTreeMap::new();
+    draw(
+        0,
+        missing,
+        1,
+        &mut known.to_vec(),
+        (deck, rules, &mut weights),
+    );
+    let total = binomial(deck.len(), missing);
+    #[allow(clippy::cast_precision_loss)] // Probabilities don't need every digit
+    let probabilities = weights
+        .into_iter()
+        .map(|(hand_type, ways)| (hand_type, ways as f64 / total as f64))
+        .collect();
+    Odds {
+        probabilities,
+        exact: true,
+    }
+}
+
+/// Draws the missing cards `sampling.samples` times at random
+fn sampled_odds(
+    known: &[Rank],
+    deck: &Deck,
+    rules: &Rules,
+    missing: usize,
+    sampling: Sampling,
+) -> Odds {
+    let mut rng = ChaCha8Rng::seed_from_u64(sampling.seed);
+    let mut cards = (0..=Rank::MAX)
+        .zip(&deck.counts)
+        .flat_map(|(rank, count)| std::iter::repeat_n(rank, *count))
+        .collect::<Vec<_>>();
+    let mut counts = BTreeMap::<_, usize>::new();
+    for _ in 0..sampling.samples {
+        let (drawn, _) = cards.partial_shuffle(&mut rng, missing);
+        let hand = known.iter().chain(drawn.iter()).copied().collect();
+        *counts
+            .entry(Hand::from_cards(hand, rules).hand_type())
+            .or_default() += 1;
+    }
+    #[allow(clippy::cast_precision_loss)] // Only an estimate
+    let probabilities = counts
+        .into_iter()
+        .map(|(hand_type, count)| (hand_type, count as f64 / sampling.samples as f64))
+        .collect();
+    Odds {
+        probabilities,
+        exact: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day7::{CardsConfig, TieBreak};
+
+    fn known_and_deck(rules: &Rules, known: &str) -> (Vec<Rank>, Deck) {
+        let known = ranks(rules, known).expect("test cards should be valid");
+        let deck = Deck::uniform(rules, 4).without(&known);
+        (known, deck)
+    }
+
+    #[test]
+    fn exact_odds_of_two_draws() {
+        let rules = CardsConfig::default().rules();
+        let (known, deck) = known_and_deck(&rules, "AAK");
+        let odds = odds(&known, &deck, &rules, Sampling::default()).expect("should have odds");
+        assert!(odds.exact);
+        // Out of the 1176 ways to draw 2 of the 49 cards left
+        let expected = [
+            (HandType::ONE_PAIR, 880),
+            (HandType::TWO_PAIR, 198),
+            (HandType::THREE_OF_A_KIND, 88),
+            (HandType::FULL_HOUSE, 9),
+            (HandType::FOUR_OF_A_KIND, 1),
+        ];
+        assert_eq!(odds.probabilities.len(), expected.len());
+        for (hand_type, ways) in expected {
+            assert!(
+                (odds.probability(hand_type) - f64::from(ways) / 1176.0).abs() < 1e-12,
+                "{hand_type}"
+            );
+        }
+        assert!((odds.at_least(HandType::FULL_HOUSE) - 10.0 / 1176.0).abs() < 1e-12);
+        assert!(!odds.probabilities.contains_key(&HandType::HIGH_CARD));
+    }
+
+    #[test]
+    fn jokers_count_as_wildcards() {
+        let jokers = CardsConfig { j_is_joker: true }.rules();
+        let (known, deck) = known_and_deck(&jokers, "JJ2");
+        let with_jokers =
+            odds(&known, &deck, &jokers, Sampling::default()).expect("should have odds");
+        assert!(with_jokers
+            .probabilities
+            .keys()
+            .all(|hand_type| *hand_type >= HandType::THREE_OF_A_KIND));
+
+        let regular = CardsConfig::default().rules();
+        let (known, deck) = known_and_deck(&regular, "JJ2");
+        let without_jokers =
+            odds(&known, &deck, &regular, Sampling::default()).expect("should have odds");
+        assert!(without_jokers.probability(HandType::TWO_PAIR) > 0.0);
+    }
+
+    #[test]
+    fn sampled_odds_are_seeded_estimates() {
+        let rules = CardsConfig::default().rules();
+        let (known, deck) = known_and_deck(&rules, "AA");
+        let exact = odds(&known, &deck, &rules, Sampling::default()).expect("should have odds");
+        let sampling = Sampling {
+            exact_limit: 0,
+            samples: 20_000,
+            seed: 7,
+        };
+        let sampled = odds(&known, &deck, &rules, sampling).expect("should have odds");
+        assert!(exact.exact && !sampled.exact);
+        assert_eq!(
+            sampled,
+            odds(&known, &deck, &rules, sampling).expect("should have odds")
+        );
+        for (hand_type, probability) in &exact.probabilities {
+            assert!(
+                (sampled.probability(*hand_type) - probability).abs() < 0.01,
+                "{hand_type}"
+            );
+        }
+    }
+
+    #[test]
+    fn odds_errors() {
+        let rules = CardsConfig::default().rules();
+        let (known, deck) = known_and_deck(&rules, "AAKKQQ");
+        assert_eq!(
+            odds(&known, &deck, &rules, Sampling::default()),
+            Err(OddsError::TooManyCards {
+                known: 6,
+                hand_size: 5
+            })
+        );
+        let deck = Deck::from_cards(&rules, "Q").expect("Q is a card");
+        assert_eq!(
+            odds(&known[..3], &deck, &rules, Sampling::default()),
+            Err(OddsError::DeckTooSmall {
+                missing: 2,
+                left: 1
+            })
+        );
+
+        // Ranks past the 13 cards of the puzzle, from rules with a larger alphabet
+        let larger = Rules::new("23456789TJQKAXY", "", 5, TieBreak::FirstToLast)
+            .expect("rules should be valid");
+        let (unknown, larger_deck) = known_and_deck(&larger, "XY");
+        let deck = Deck::uniform(&rules, 4).without(&unknown);
+        assert_eq!(deck.len(), 52);
+        assert_eq!(
+            odds(&unknown, &deck, &rules, Sampling::default()),
+            Err(OddsError::UnknownCard { rank: 13 })
+        );
+        assert_eq!(
+            odds(&known[..3], &larger_deck, &rules, Sampling::default()),
+            Err(OddsError::UnknownCard { rank: 13 })
+        );
+
+        let (known, deck) = known_and_deck(&rules, "A");
+        let no_samples = Sampling {
+            exact_limit: 0,
+            samples: 0,
+            seed: 0,
+        };
+        assert_eq!(
+            odds(&known, &deck, &rules, no_samples),
+            Err(OddsError::NoSamples)
+        );
+    }
+}
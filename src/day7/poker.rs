@@ -0,0 +1,375 @@
+//! Actual poker hands, of suited cards, next to the Camel Cards ones which only look at how many
+//! cards of each rank there are
+//!
+//! Cards are written rank first & suit second, like `As` for the ace of spades or `Td` for the ten
+//! of diamonds, with or without spaces between them. Hands are ranked by category, from high card
+//! to straight flush, then by the ranks that matter in that category, most significant first: the
+//! highest card of a straight, the ranks of the groups from the largest, then the kickers. The ace
+//! also plays low in the wheel, `A2345`, the lowest straight.
+
+use super::Rank;
+use crate::parse::{Line, Location};
+use itertools::Itertools;
+use std::{
+    cmp::{Ordering, Reverse},
+    fmt,
+};
+
+/// Ranks from the lowest to the highest, as written in the notation
+const RANKS: &str = "23456789TJQKA";
+const SUITS: &str = "cdhs";
+pub const HAND_SIZE: usize = 5;
+/// Most cards to pick the best hand from, as in Texas hold'em
+pub const MAX_CARDS: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Card {
+    /// From 0 for a 2 to 12 for an ace
+    pub rank: Rank,
+    pub suit: Suit,
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rank = RANKS.as_bytes()[usize::from(self.rank)] as char;
+        let suit = SUITS.as_bytes()[self.suit as usize] as char;
+        write!(f, "{rank}{suit}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Category::HighCard => "high card",
+            Category::OnePair => "one pair",
+            Category::TwoPair => "two pair",
+            Category::ThreeOfAKind => "three of a kind",
+            Category::Straight => "straight",
+            Category::Flush => "flush",
+            Category::FullHouse => "full house",
+            Category::FourOfAKind => "four of a kind",
+            Category::StraightFlush => "straight flush",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The 5 cards that make a poker hand, ranking by category & then by the ranks that break ties in
+/// it, suits never breaking ties
+#[derive(Debug, Clone)]
+pub struct PokerHand {
+    category: Category,
+    /// Most significant first
+    tie_breakers: Vec<Rank>,
+    cards: [Card; HAND_SIZE],
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PokerError {
+    UnknownRank { location: Location, found: char },
+    UnknownSuit { location: Location, found: char },
+    MissingSuit { location: Location },
+    DuplicateCard { location: Location, card: Card },
+    WrongCardCount { location: Location, found: usize },
+}
+
+impl fmt::Display for PokerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PokerError::UnknownRank { location, found } => {
+                write!(
+                    f,
+                    "{location}: expected a rank, one of {RANKS}, found `{found}`"
+                )
+            }
+            PokerError::UnknownSuit { location, found } => {
+                write!(
+                    f,
+                    "{location}: expected a suit, one of {SUITS}, found `{found}`"
+                )
+            }
+            PokerError::MissingSuit { location } => {
+                write!(f, "{location}: expected a suit after the rank")
+            }
+            PokerError::DuplicateCard { location, card } => {
+                write!(
+                    f,
+                    "{location}: expected each card once, found `{card}` again"
+                )
+            }
+            PokerError::WrongCardCount { location, found } => write!(
+                f,
+                "{location}: expected {HAND_SIZE} to {MAX_CARDS} cards, found {found}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PokerError {}
+
+/// Parses the cards written in `text`, which should be a slice of `line` so that errors can point
+/// into it
+///
+/// # Panics
+///
+/// Never, as each of the 13 ranks fits in a [`Rank`]
+pub fn parse_cards(line: &Line, text: &str) -> Result<Vec<Card>, PokerError> {
+    let mut cards = Vec::new();
+    let mut chars = text
+        .char_indices()
+        .filter(|(_, char)| !char.is_whitespace());
+    while let Some((offset, rank)) = chars.next() {
+        let location = line.locate(&text[offset..]);
+        let rank = RANKS
+            .find(rank)
+            .ok_or(PokerError::UnknownRank {
+                location,
+                found: rank,
+            })?
+            .try_into()
+            .expect("there are 13 ranks");
+        let (suit_offset, suit) = chars.next().ok_or(PokerError::MissingSuit {
+            location: line.locate(&text[text.len()..]),
+        })?;
+        let suit = match suit {
+            'c' => Suit::Clubs,
+            'd' => Suit::Diamonds,
+            'h' => Suit::Hearts,
+            's' => Suit::Spades,
+            found => {
+                return Err(PokerError::UnknownSuit {
+                    location: line.locate(&text[suit_offset..]),
+                    found,
+                })
+            }
+        };
+        let card = Card { rank, suit };
+        if cards.contains(&card) {
+            return Err(PokerError::DuplicateCard { location, card });
+        }
+        cards.push(card);
+    }
+    Ok(cards)
+}
+
+impl PokerHand {
+    /// Best hand of the cards written in `text`, a slice of `line`, of which there should be 5 to 7
+    pub fn from_notation(line: &Line, text: &str) -> Result<Self, PokerError> {
+        let cards = parse_cards(line, text)?;
+        Self::best_of(&cards).ok_or(PokerError::WrongCardCount {
+            location: line.locate(text),
+            found: cards.len(),
+        })
+    }
+
+    /// Best hand that 5 of `cards` make, unless there are fewer than 5 or more than 7 of them
+    ///
+    /// # Panics
+    ///
+    /// Never, as the combinations taken are of 5 cards
+    pub fn best_of(cards: &[Card]) -> Option<Self> {
+        if !(HAND_SIZE..=MAX_CARDS).contains(&cards.len()) {
+            return None;
+        }
+        cards
+            .iter()
+            .copied()
+            .combinations(HAND_SIZE)
+            .map(|cards| Self::from_cards(cards.try_into().expect("combinations of 5 cards")))
+            .max()
+    }
+
+    pub fn from_cards(cards: [Card; HAND_SIZE]) -> Self {
+        let counts = cards.iter().map(|card| card.rank).counts();
+        // Ranks of the largest groups first, the highest first among groups of the same size
+        let groups = counts
+            .iter()
+            .map(|(rank, count)| (*count, *rank))
+            .sorted_unstable_by_key(|group| Reverse(*group))
+            .collect::<Vec<_>>();
+        let ranks = groups.iter().map(|(_, rank)| *rank).collect::<Vec<_>>();
+        let is_flush = cards.iter().map(|card| card.suit).all_equal();
+        let straight_high = match ranks.as_slice() {
+            [high, .., low] if ranks.len() == HAND_SIZE && high - low == 4 => Some(*high),
+            // The wheel, where the ace plays low & the 5 is the highest card
+            [12, 3, 2, 1, 0] => Some(3),
+            _ => None,
+        };
+        let group_sizes = groups.iter().map(|(count, _)| *count).collect::<Vec<_>>();
+        let (category, tie_breakers) = match (straight_high, is_flush, group_sizes.as_slice()) {
+            (Some(high), true, _) => (Category::StraightFlush, vec![high]),
+            (_, _, [4, 1]) => (Category::FourOfAKind, ranks),
+            (_, _, [3, 2]) => (Category::FullHouse, ranks),
+            (_, true, _) => (Category::Flush, ranks),
+            (Some(high), false, _) => (Category::Straight, vec![high]),
+            (_, _, [3, ..]) => (Category::ThreeOfAKind, ranks),
+            (_, _, [2, 2, 1]) => (Category::TwoPair, ranks),
+            (_, _, [2, ..]) => (Category::OnePair, ranks),
+            _ => (Category::HighCard, ranks),
+        };
+        Self {
+            category,
+            tie_breakers,
+            cards,
+        }
+    }
+
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    pub fn cards(&self) -> &[Card; HAND_SIZE] {
+        &self.cards
+    }
+}
+
+impl Ord for PokerHand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.category, &self.tie_breakers).cmp(&(other.category, &other.tie_breakers))
+    }
+}
+
+impl PartialOrd for PokerHand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for PokerHand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PokerHand {}
+
+impl fmt::Display for PokerHand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.category, self.cards.iter().join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(text: &str) -> PokerHand {
+        PokerHand::from_notation(&Line::new(0, text), text).expect("test hands should be valid")
+    }
+
+    #[test]
+    fn categories() {
+        let ladder = [
+            ("2c5d9hJsKc", Category::HighCard),
+            ("9c9dAh4s2c", Category::OnePair),
+            ("9c9d4h4s2c", Category::TwoPair),
+            ("9c9d9h4s2c", Category::ThreeOfAKind),
+            ("Ah2c3d4s5h", Category::Straight),
+            ("2h7h9hJhKh", Category::Flush),
+            ("9c9d9h4s4c", Category::FullHouse),
+            ("9c9d9h9s2c", Category::FourOfAKind),
+            ("5s6s7s8s9s", Category::StraightFlush),
+        ];
+        for (text, category) in ladder {
+            assert_eq!(hand(text).category(), category, "{text}");
+        }
+        for pair in ladder.windows(2) {
+            assert!(hand(pair[0].0) < hand(pair[1].0), "{pair:?}");
+        }
+        assert_eq!(
+            hand("Td Jd Qd Kd Ad").to_string(),
+            "straight flush (Td Jd Qd Kd Ad)"
+        );
+    }
+
+    #[test]
+    fn straights_and_kickers() {
+        // The wheel is the lowest straight, the ace playing low
+        assert!(hand("Ah2c3d4s5h") < hand("2c3d4s5h6c"));
+        assert!(hand("Ah2h3h4h5h") < hand("2d3d4d5d6d"));
+        assert!(hand("TcJdQhKsAc") > hand("9cTdJhQsKc"));
+        // No wrapping around the ace
+        assert_eq!(hand("QcKdAh2s3c").category(), Category::HighCard);
+        // Groups first, then the kickers, suits never breaking ties
+        assert!(hand("9c9dAh4s2c") > hand("9h9sKh4c2d"));
+        assert!(hand("9c9d4h4sAc") > hand("8c8d7h7sAd"));
+        assert!(hand("3c3d3hAsAc") < hand("4c4d4h2s2c"));
+        assert!(hand("2h7h9hJhAh") > hand("3d7d9dJdKd"));
+        assert_eq!(hand("2c5d9hJsKc"), hand("2d5h9sJcKd"));
+    }
+
+    #[test]
+    fn best_five_of_seven() {
+        // A flush in hearts beats the straight on the board
+        let best = hand("Ah Kh 5h 6h 7h 8c 9d");
+        assert_eq!(best.category(), Category::Flush);
+        assert_eq!(best.to_string(), "flush (Ah Kh 5h 6h 7h)");
+        // The best kickers are kept
+        assert_eq!(hand("2c2d As Kh 9d 4c 3h"), hand("2c2d As Kh 9d"));
+        // The wheel, out of 7 cards with a pair in them
+        assert_eq!(hand("As 2d 3c 4h 5s 5d Kc").category(), Category::Straight);
+    }
+
+    #[test]
+    fn notation_errors() {
+        let parse = |text| PokerHand::from_notation(&Line::new(0, text), text);
+        assert_eq!(
+            parse("AsKd1c"),
+            Err(PokerError::UnknownRank {
+                location: Location { line: 1, column: 5 },
+                found: '1',
+            })
+        );
+        assert_eq!(
+            parse("As Kx"),
+            Err(PokerError::UnknownSuit {
+                location: Location { line: 1, column: 5 },
+                found: 'x',
+            })
+        );
+        assert_eq!(
+            parse("AsK"),
+            Err(PokerError::MissingSuit {
+                location: Location { line: 1, column: 4 },
+            })
+        );
+        assert_eq!(
+            parse("AsKdAs"),
+            Err(PokerError::DuplicateCard {
+                location: Location { line: 1, column: 5 },
+                card: Card {
+                    rank: 12,
+                    suit: Suit::Spades,
+                },
+            })
+        );
+        assert_eq!(
+            parse("AsKdQc"),
+            Err(PokerError::WrongCardCount {
+                location: Location { line: 1, column: 1 },
+                found: 3,
+            })
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! The full ranking behind the total winnings, hand by hand, to settle why a hand ranks where it
+//! does
+//!
+//! ```text
+//! rank  hand   type            as played  bid  winnings  tie broken at
+//!    1  32T3K  one pair                   765       765
+//!    2  KK677  two pair                    28        56
+//!    3  T55J5  four of a kind  T5555      684      2052
+//!    4  QQQJA  four of a kind  QQQQA      483      1932  card 1
+//!    5  KTJJT  four of a kind  KTTTT      220      1100  card 1
+//! ```
+
+use super::{ranked_hands, HandType, ParseError, Rank, Rules};
+use serde::Serialize;
+use std::fmt::Write;
+
+/// A hand along with where it ranks & why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RankedHand {
+    /// From 1 for the weakest hand
+    pub rank: usize,
+    pub cards: String,
+    pub bid: u32,
+    pub winnings: u32,
+    pub hand_type: HandType,
+    /// The cards with the wildcards replaced by the card they stand for, if there are any
+    pub as_played: Option<String>,
+    /// Position of the card, from 1, that ranks the hand above the one ranked just below it, when
+    /// both are of the same type
+    pub tie_break: Option<usize>,
+}
+
+/// Hands of `input` from the weakest to the strongest under `rules`
+pub fn ranking(input: &str, rules: &Rules) -> Result<Vec<RankedHand>, ParseError> {
+    let hands = ranked_hands(input, rules)?;
+    let cards =
+        |ranks: &mut dyn Iterator<Item = Rank>| ranks.map(|rank| rules.card(rank)).collect();
+    Ok(hands
+        .iter()
+        .enumerate()
+        .map(|(index, (hand, bid))| {
+            let below = index.checked_sub(1).map(|below| &hands[below].0);
+            RankedHand {
+                rank: index + 1,
+                cards: cards(&mut hand.cards().iter().copied()),
+                bid: *bid,
+                winnings: bid * (index as u32 + 1),
+                hand_type: hand.hand_type(),
+                as_played: hand.substitute().map(|substitute| {
+                    cards(&mut hand.cards().iter().map(|card| {
+                        if rules.is_wildcard(*card) {
+                            substitute
+                        } else {
+                            *card
+                        }
+                    }))
+                }),
+                tie_break: below
+                    .and_then(|below| hand.tie_break_position(below))
+                    .map(|position| position + 1),
+            }
+        })
+        .collect())
+}
+
+/// Lines up the ranking in columns, as in the [module docs](self)
+///
+/// # Panics
+///
+/// Never, as writing to a `String` can't fail
+pub fn render_table(ranking: &[RankedHand]) -> String {
+    let header = [
+        "rank",
+        "hand",
+        "type",
+        "as played",
+        "bid",
+        "winnings",
+        "tie broken at",
+    ];
+    // Numbers are aligned to the right, text to the left
+    let right_aligned = [true, false, false, false, true, true, false];
+    let rows = ranking.iter().map(|hand| {
+        [
+            hand.rank.to_string(),
+            hand.cards.clone(),
+            hand.hand_type.to_string(),
+            hand.as_played.clone().unwrap_or_default(),
+            hand.bid.to_string(),
+            hand.winnings.to_string(),
+            hand.tie_break
+                .map(|position| format!("card {position}"))
+                .unwrap_or_default(),
+        ]
+    });
+    let rows = [header.map(String::from)]
+        .into_iter()
+        .chain(rows)
+        .collect::<Vec<_>>();
+    let widths = (0..header.len())
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+    let mut table = String::new();
+    for row in &rows {
+        let mut line = String::new();
+        for (column, cell) in row.iter().enumerate() {
+            let width = widths[column];
+            if right_aligned[column] {
+                write!(line, "{cell:>width$}  ")
+            } else {
+                write!(line, "{cell:<width$}  ")
+            }
+            .expect("writing to a String can't fail");
+        }
+        writeln!(table, "{}", line.trim_end()).expect("writing to a String can't fail");
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day7::{tests::EXAMPLE_INPUT, CardsConfig};
+    use indoc::indoc;
+
+    #[test]
+    fn explains_the_example_ranking() {
+        let without_jokers = ranking(EXAMPLE_INPUT, &CardsConfig::default().rules())
+            .expect("example should be valid");
+        assert_eq!(
+            render_table(&without_jokers),
+            indoc! {"
+                rank  hand   type             as played  bid  winnings  tie broken at
+                   1  32T3K  one pair                    765       765
+                   2  KTJJT  two pair                    220       440
+                   3  KK677  two pair                     28        84  card 2
+                   4  T55J5  three of a kind             684      2736
+                   5  QQQJA  three of a kind             483      2415  card 1
+            "}
+        );
+
+        let ranking = ranking(EXAMPLE_INPUT, &CardsConfig { j_is_joker: true }.rules())
+            .expect("example should be valid");
+        assert_eq!(ranking.iter().map(|hand| hand.winnings).sum::<u32>(), 5905);
+        assert_eq!(
+            ranking[4],
+            RankedHand {
+                rank: 5,
+                cards: "KTJJT".to_string(),
+                bid: 220,
+                winnings: 1100,
+                hand_type: HandType::FOUR_OF_A_KIND,
+                as_played: Some("KTTTT".to_string()),
+                tie_break: Some(1),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&ranking[0]).expect("should serialize"),
+            serde_json::json!({
+                "rank": 1,
+                "cards": "32T3K",
+                "bid": 765,
+                "winnings": 765,
+                "hand_type": "one pair",
+                "as_played": null,
+                "tie_break": null,
+            })
+        );
+    }
+}
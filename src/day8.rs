@@ -1,7 +1,12 @@
+use crate::{
+    parse::{self, Location},
+    solution::{variants, Solution, Variant},
+};
 use itertools::Itertools;
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 #[derive(Debug)]
+#[allow(clippy::struct_field_names)] // `left` & `right` alone would read like directions
 pub struct Node {
     _element: String,
     left_element: String,
@@ -17,30 +22,129 @@ pub enum Instruction {
 pub type Network = HashMap<String, Node>;
 pub type Instructions = Vec<Instruction>;
 
-#[allow(clippy::unwrap_used)]
-pub fn generator(input: &str) -> (Instructions, Network) {
-    let mut lines = input.lines();
-    let instructions = lines
-        .next()
-        .unwrap()
-        .chars()
-        .map(|c| match c {
-            'L' => Instruction::L,
-            'R' => Instruction::R,
-            _ => unreachable!(),
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    MissingInstructions {
+        location: Location,
+    },
+    UnknownInstruction {
+        location: Location,
+        found: char,
+    },
+    MalformedNode {
+        location: Location,
+        expected: &'static str,
+    },
+    UnknownElement {
+        location: Location,
+        found: String,
+    },
+    Walk(WalkError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingInstructions { location } => {
+                write!(f, "{location}: expected a line of L/R instructions")
+            }
+            ParseError::UnknownInstruction { location, found } => {
+                write!(f, "{location}: expected `L` or `R`, found `{found}`")
+            }
+            ParseError::MalformedNode { location, expected } => {
+                write!(f, "{location}: expected `{expected}` in `AAA = (BBB, CCC)`")
+            }
+            ParseError::UnknownElement { location, found } => {
+                write!(
+                    f,
+                    "{location}: expected `{found}` to have its own node line"
+                )
+            }
+            ParseError::Walk(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<WalkError> for ParseError {
+    fn from(error: WalkError) -> Self {
+        ParseError::Walk(error)
+    }
+}
+
+/// Why the network can't be walked for a part, which the generator can't rule out, as the example
+/// of each part lacks the nodes that the other part starts or ends at
+#[derive(Debug, PartialEq, Eq)]
+pub enum WalkError {
+    MissingNode { element: &'static str },
+    NoNodeEndingIn { last: char },
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WalkError::MissingNode { element } => {
+                write!(f, "expected a node line for `{element}`")
+            }
+            WalkError::NoNodeEndingIn { last } => {
+                write!(f, "expected a node line for an element ending in `{last}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WalkError {}
+
+/// Elements ending in `last`, failing if there are none
+fn elements_ending_in(network: &Network, last: char) -> Result<Vec<&String>, WalkError> {
+    let elements = network
+        .keys()
+        .filter(|key| key.ends_with(last))
+        .collect_vec();
+    if elements.is_empty() {
+        return Err(WalkError::NoNodeEndingIn { last });
+    }
+    Ok(elements)
+}
+
+pub fn generator(input: &str) -> Result<(Instructions, Network), ParseError> {
+    let mut lines = parse::lines(input);
+    let instructions_line = lines.next().filter(|line| !line.text.is_empty()).ok_or(
+        ParseError::MissingInstructions {
+            location: Location { line: 1, column: 1 },
+        },
+    )?;
+    let instructions = instructions_line
+        .text
+        .char_indices()
+        .map(|(offset, c)| match c {
+            'L' => Ok(Instruction::L),
+            'R' => Ok(Instruction::R),
+            found => Err(ParseError::UnknownInstruction {
+                location: instructions_line.at_byte(offset),
+                found,
+            }),
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
     lines.next();
     let mut nodes = HashMap::new();
+    let mut next_element_locations = Vec::new();
     for line in lines {
-        let (element, next_elements) = line.split_once(" = ").unwrap();
+        let malformed = |expected| ParseError::MalformedNode {
+            location: line.end(),
+            expected,
+        };
+        let (element, next_elements) = line.text.split_once(" = ").ok_or(malformed(" = "))?;
         let (left_element, right_element) = next_elements
             .strip_prefix('(')
-            .unwrap()
+            .ok_or(malformed("("))?
             .strip_suffix(')')
-            .unwrap()
+            .ok_or(malformed(")"))?
             .split_once(", ")
-            .unwrap();
+            .ok_or(malformed(", "))?;
+        next_element_locations.push((line.locate(left_element), left_element));
+        next_element_locations.push((line.locate(right_element), right_element));
         nodes.insert(
             element.to_string(),
             Node {
@@ -50,18 +154,45 @@ pub fn generator(input: &str) -> (Instructions, Network) {
             },
         );
     }
-    (instructions, nodes)
+    // Walking the network indexes it by element, so make sure that can't fail
+    if let Some((location, element)) = next_element_locations
+        .into_iter()
+        .find(|(_, element)| !nodes.contains_key(*element))
+    {
+        return Err(ParseError::UnknownElement {
+            location,
+            found: element.to_string(),
+        });
+    }
+    Ok((instructions, nodes))
 }
 
+/// Steps from the node of `start_element` until the predicate holds for the next element, which
+/// the generator ensures has a node of its own
 pub fn count_steps<P>(
     (instructions, network): &(Instructions, Network),
-    start_element: &str,
+    start_element: &'static str,
+    predicate: &mut P,
+) -> Result<u32, WalkError>
+where
+    P: FnMut(&String) -> bool,
+{
+    let start_node = network.get(start_element).ok_or(WalkError::MissingNode {
+        element: start_element,
+    })?;
+    Ok(walk(instructions, network, start_node, predicate))
+}
+
+fn walk<P>(
+    instructions: &Instructions,
+    network: &Network,
+    start_node: &Node,
     predicate: &mut P,
 ) -> u32
 where
     P: FnMut(&String) -> bool,
 {
-    let mut cur_node = &network[start_element];
+    let mut cur_node = start_node;
     let mut instructions_followed = 0;
     for instruction in instructions.iter().cycle() {
         instructions_followed += 1;
@@ -77,23 +208,34 @@ where
     instructions_followed
 }
 
-pub fn part1(instructions_and_network: &(Instructions, Network)) -> u32 {
+pub fn part1(instructions_and_network: &(Instructions, Network)) -> Result<u32, ParseError> {
+    if !instructions_and_network.1.contains_key("ZZZ") {
+        // Walking from `AAA` would never end
+        return Err(WalkError::MissingNode { element: "ZZZ" }.into());
+    }
     let mut final_step_predicate = |next_element: &_| next_element == "ZZZ";
-    count_steps(instructions_and_network, "AAA", &mut final_step_predicate)
+    Ok(count_steps(
+        instructions_and_network,
+        "AAA",
+        &mut final_step_predicate,
+    )?)
 }
 
-pub fn part2(instructions_and_network: &(Instructions, Network)) -> u64 {
-    instructions_and_network
-        .1
-        .keys()
-        .filter(|key| key.ends_with('A'))
-        .map(|key| {
-            let mut final_step_predicate = |next_element: &String| next_element.ends_with('Z');
-            let steps = count_steps(instructions_and_network, key, &mut final_step_predicate);
-            u64::from(steps)
-        })
-        .reduce(u64::lcm)
-        .expect("element ending with Z should exist for each starting element ending with A")
+pub fn part2((instructions, network): &(Instructions, Network)) -> Result<u64, ParseError> {
+    let start_elements = elements_ending_in(network, 'A')?;
+    elements_ending_in(network, 'Z')?;
+    let mut steps = 1;
+    for element in start_elements {
+        let mut final_step_predicate = |next_element: &String| next_element.ends_with('Z');
+        let ghost_steps = walk(
+            instructions,
+            network,
+            &network[element],
+            &mut final_step_predicate,
+        );
+        steps = u64::lcm(steps, u64::from(ghost_steps));
+    }
+    Ok(steps)
 }
 
 trait Arithmetic<T> {
@@ -119,14 +261,12 @@ macro_rules! impl_arithmetic_for_usize {
 
 impl_arithmetic_for_usize!(u64);
 
-#[allow(dead_code)]
-pub fn part2_naive((instructions, network): &(Instructions, Network)) -> u32 {
-    let mut cur_nodes = network
-        .iter()
-        .filter(|(key, _)| key.ends_with('A'))
-        .map(|(_, val)| val)
-        .take(2)
+pub fn part2_naive((instructions, network): &(Instructions, Network)) -> Result<u32, ParseError> {
+    let mut cur_nodes = elements_ending_in(network, 'A')?
+        .into_iter()
+        .map(|element| &network[element])
         .collect_vec();
+    elements_ending_in(network, 'Z')?;
     let mut instructions_followed = 0;
     // let total_instructions = instructions.len() as u32;
     // println!("{total_instructions}");
@@ -151,7 +291,24 @@ pub fn part2_naive((instructions, network): &(Instructions, Network)) -> u32 {
             .map(|next_element| &network[*next_element])
             .collect();
     }
-    instructions_followed
+    Ok(instructions_followed)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 8;
+    type Parsed = (Instructions, Network);
+    type Answer = u64;
+    type Error = ParseError;
+    const PARTS: [&'static [Variant<Self>]; 2] = [
+        variants![fallible: part1],
+        variants![fallible: part2; slow: part2_naive],
+    ];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error> {
+        generator(input)
+    }
 }
 
 #[cfg(test)]
@@ -196,13 +353,88 @@ mod tests {
     #[test]
     fn part1_example() {
         println!("{:?}", generator(EXAMPLE_INPUT_1));
-        assert_eq!(part1(&generator(EXAMPLE_INPUT_1)), 2);
-        assert_eq!(part1(&generator(EXAMPLE_INPUT_2)), 6);
+        assert_eq!(
+            generator(EXAMPLE_INPUT_1).and_then(|input| part1(&input)),
+            Ok(2)
+        );
+        assert_eq!(
+            generator(EXAMPLE_INPUT_2).and_then(|input| part1(&input)),
+            Ok(6)
+        );
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2_naive(&generator(EXAMPLE_INPUT_3)), 6);
-        assert_eq!(part2(&generator(EXAMPLE_INPUT_3)), 6);
+        assert_eq!(
+            generator(EXAMPLE_INPUT_3).and_then(|input| part2_naive(&input)),
+            Ok(6)
+        );
+        assert_eq!(
+            generator(EXAMPLE_INPUT_3).and_then(|input| part2(&input)),
+            Ok(6)
+        );
+    }
+
+    #[test]
+    fn walk_errors() {
+        // Cut off after the instructions
+        let input = generator("L").expect("instructions alone should parse");
+        assert_eq!(
+            part1(&input),
+            Err(ParseError::Walk(WalkError::MissingNode { element: "ZZZ" }))
+        );
+        assert_eq!(
+            part2(&input),
+            Err(ParseError::Walk(WalkError::NoNodeEndingIn { last: 'A' }))
+        );
+        assert_eq!(
+            part2_naive(&input),
+            Err(ParseError::Walk(WalkError::NoNodeEndingIn { last: 'A' }))
+        );
+        assert_eq!(
+            generator(EXAMPLE_INPUT_3).and_then(|input| part1(&input)),
+            Err(ParseError::Walk(WalkError::MissingNode { element: "ZZZ" }))
+        );
+        let no_start = EXAMPLE_INPUT_1.replace("AAA", "ABC");
+        assert_eq!(
+            generator(&no_start).and_then(|input| part1(&input)),
+            Err(ParseError::Walk(WalkError::MissingNode { element: "AAA" }))
+        );
+        let no_end = EXAMPLE_INPUT_3.replace('Z', "Y");
+        assert_eq!(
+            generator(&no_end).and_then(|input| part2(&input)),
+            Err(ParseError::Walk(WalkError::NoNodeEndingIn { last: 'Z' }))
+        );
+    }
+
+    #[test]
+    fn generator_errors() {
+        assert_eq!(
+            generator(&EXAMPLE_INPUT_2.replace("LLR", "LXR")).err(),
+            Some(ParseError::UnknownInstruction {
+                location: Location { line: 1, column: 2 },
+                found: 'X',
+            })
+        );
+        assert_eq!(
+            generator(&EXAMPLE_INPUT_2.replace("(AAA, ZZZ)", "(AAA, ZZZ")).err(),
+            Some(ParseError::MalformedNode {
+                location: Location {
+                    line: 4,
+                    column: 16
+                },
+                expected: ")",
+            })
+        );
+        assert_eq!(
+            generator(&EXAMPLE_INPUT_2.replace("(AAA, ZZZ)", "(AAA, YYY)")).err(),
+            Some(ParseError::UnknownElement {
+                location: Location {
+                    line: 4,
+                    column: 13
+                },
+                found: "YYY".to_string(),
+            })
+        );
     }
 }
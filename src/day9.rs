@@ -1,35 +1,83 @@
-type History = Vec<i32>;
-type Sequence = Vec<i32>;
+use crate::{
+    parse::{self, Location},
+    solution::{variants, Solution, Variant},
+};
+use std::fmt;
 
-#[allow(clippy::unwrap_used)]
-pub fn generator(input: &str) -> Vec<History> {
-    input
-        .lines()
+pub type History = Vec<i32>;
+pub type Sequence = Vec<i32>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    EmptyHistory { location: Location },
+    InvalidNumber { location: Location, found: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::EmptyHistory { location } => {
+                write!(f, "{location}: expected at least one number in the history")
+            }
+            ParseError::InvalidNumber { location, found } => {
+                write!(f, "{location}: expected a number, found `{found}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn generator(input: &str) -> Result<Vec<History>, ParseError> {
+    parse::lines(input)
         .map(|line| {
-            line.split_whitespace()
-                .map(|num_str| num_str.parse::<i32>().unwrap())
-                .collect()
+            let history = line
+                .text
+                .split_whitespace()
+                .map(|num_str| {
+                    num_str
+                        .parse::<i32>()
+                        .map_err(|_| ParseError::InvalidNumber {
+                            location: line.locate(num_str),
+                            found: num_str.to_string(),
+                        })
+                })
+                .collect::<Result<History, _>>()?;
+            if history.is_empty() {
+                return Err(ParseError::EmptyHistory {
+                    location: line.start(),
+                });
+            }
+            Ok(history)
         })
         .collect()
 }
 
+/// The history followed by the differences between its numbers, then between those, & so on until
+/// they're all zeroes, or down to a single number, which is taken to repeat
 pub fn create_sequences(history: &History) -> Vec<Sequence> {
-    let mut sequences = vec![history.to_owned()];
+    let mut sequences = Vec::new();
+    let mut sequence = history.to_owned();
     loop {
-        let diff_sequence = sequences
-            .last()
-            .expect("sequences should not be empty")
+        let diff_sequence = sequence
             .windows(2)
             .map(|nums| nums[1] - nums[0])
             .collect::<Vec<_>>();
+        sequences.push(sequence);
+        if diff_sequence.is_empty() {
+            break;
+        }
         if diff_sequence.iter().all(|num| *num == 0) {
             break sequences.push(diff_sequence);
         }
-        sequences.push(diff_sequence);
+        sequence = diff_sequence;
     }
     sequences
 }
 
+/// # Panics
+///
+/// If a history is empty, which the [`generator`] doesn't let through
 pub fn part1(report: &[History]) -> i32 {
     report
         .iter()
@@ -42,6 +90,9 @@ pub fn part1(report: &[History]) -> i32 {
         .sum()
 }
 
+/// # Panics
+///
+/// Like [`part1`]
 pub fn part2(report: &[History]) -> i32 {
     report
         .iter()
@@ -54,6 +105,20 @@ pub fn part2(report: &[History]) -> i32 {
         .sum()
 }
 
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 9;
+    type Parsed = Vec<History>;
+    type Answer = i32;
+    type Error = ParseError;
+    const PARTS: [&'static [Variant<Self>]; 2] = [variants![part1], variants![part2]];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error> {
+        generator(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -69,11 +134,40 @@ mod tests {
     #[test]
     fn part1_example() {
         // println!("{:?}", generator(EXAMPLE_INPUT));
-        assert_eq!(part1(&generator(EXAMPLE_INPUT)), 114);
+        assert_eq!(
+            generator(EXAMPLE_INPUT).map(|report| part1(&report)),
+            Ok(114)
+        );
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&generator(EXAMPLE_INPUT)), 2);
+        assert_eq!(generator(EXAMPLE_INPUT).map(|report| part2(&report)), Ok(2));
+    }
+
+    #[test]
+    fn short_histories() {
+        // A single number can only be extrapolated as repeating
+        assert_eq!(generator("-1").map(|report| part1(&report)), Ok(-1));
+        assert_eq!(generator("-1").map(|report| part2(&report)), Ok(-1));
+        assert_eq!(generator("1 5").map(|report| part1(&report)), Ok(9));
+        assert_eq!(generator("1 5").map(|report| part2(&report)), Ok(-3));
+    }
+
+    #[test]
+    fn generator_errors() {
+        assert_eq!(
+            generator("0 3 6 9 12 15\n1 3 6 1O 15 21").err(),
+            Some(ParseError::InvalidNumber {
+                location: Location { line: 2, column: 7 },
+                found: "1O".to_string(),
+            })
+        );
+        assert_eq!(
+            generator("0 3 6 9 12 15\n\n1 3 6 10 15 21").err(),
+            Some(ParseError::EmptyHistory {
+                location: Location { line: 2, column: 1 },
+            })
+        );
     }
 }
@@ -0,0 +1,92 @@
+//! Differential tests: every variant of a part should give the same answer for random valid inputs
+
+use crate::{
+    answers::VariantAnswers,
+    gen::{
+        self, day1::CalibrationDocument, day5::Almanac, day6::RaceSheet, day8::Network, PuzzleInput,
+    },
+    solution::Registry,
+};
+use std::panic::{self, AssertUnwindSafe};
+
+const CASES: u64 = 200;
+
+/// Runs every variant of a part, slow ones included as generated inputs are small enough for them.
+/// Panics are treated as failures of all variants, so that they get shrunk like disagreements.
+fn run_variants(registry: &Registry, day: u8, part: u8, input: &str) -> VariantAnswers {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        registry.solve(day, part, input, |_| true)
+    }))
+    .unwrap_or_else(|_| vec![("(panicked)", Err("panicked".to_string()))])
+}
+
+fn agree(answers: &VariantAnswers) -> bool {
+    answers.iter().all(|(_, answer)| answer.is_ok())
+        && answers.windows(2).all(|pair| pair[0].1 == pair[1].1)
+}
+
+/// Checks inputs of sizes cycling through `1..=max_size`, shrinking any input the variants
+/// disagree on to a minimal reproducer
+fn assert_variants_agree<P: PuzzleInput>(max_size: usize, day: u8, part: u8) {
+    let registry = Registry::new();
+    for seed in 0..CASES {
+        let size = 1 + seed as usize % max_size;
+        let input = gen::generate::<P>(seed, size);
+        let disagrees = |input: &P| !agree(&run_variants(&registry, day, part, &input.to_string()));
+        if disagrees(&input) {
+            let minimal = gen::shrink(input, disagrees).to_string();
+            let answers = run_variants(&registry, day, part, &minimal)
+                .iter()
+                .map(|(variant, answer)| format!("{variant}: {answer:?}"))
+                .collect::<Vec<_>>();
+            panic!(
+                "variants disagree for seed {seed} & size {size}, shrunk to:\n{minimal}\n{}",
+                answers.join("\n")
+            );
+        }
+    }
+}
+
+#[test]
+fn generated_inputs_are_solved() {
+    let registry = Registry::new();
+    for day in registry.days() {
+        for seed in 0..20 {
+            let size = 1 + seed as usize % 8;
+            let input = gen::generate_input(day, seed, size).expect("every day has a generator");
+            for part in 1..=2 {
+                let answers = run_variants(&registry, day, part, &input);
+                assert!(
+                    agree(&answers),
+                    "day {day} part {part} failed for seed {seed} & size {size}:\n{input}\n{answers:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn day1_part2_variants_agree() {
+    assert_variants_agree::<CalibrationDocument>(20, 1, 2);
+}
+
+#[test]
+fn day5_part1_variants_agree() {
+    assert_variants_agree::<Almanac>(10, 5, 1);
+}
+
+#[test]
+fn day5_part2_variants_agree() {
+    assert_variants_agree::<Almanac>(10, 5, 2);
+}
+
+#[test]
+fn day6_variants_agree() {
+    assert_variants_agree::<RaceSheet>(8, 6, 1);
+    assert_variants_agree::<RaceSheet>(8, 6, 2);
+}
+
+#[test]
+fn day8_part2_variants_agree() {
+    assert_variants_agree::<Network>(6, 8, 2);
+}
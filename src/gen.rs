@@ -0,0 +1,113 @@
+//! Seeded generators of valid puzzle inputs, since actual inputs can't be checked in, for use in
+//! tests, fuzzers & benchmarks alike
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+
+pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+/// Puzzle input that can be randomly generated, and shrunk while staying valid
+pub trait PuzzleInput: fmt::Display + Clone {
+    /// Generates an input whose dimensions (line counts, magnitude of numbers etc.) grow with `size`
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self;
+
+    /// Slightly smaller variations of this input that are still valid
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+/// Generates the same input for the same seed & size on every platform
+pub fn generate<P: PuzzleInput>(seed: u64, size: usize) -> P {
+    P::generate(&mut ChaCha8Rng::seed_from_u64(seed), size)
+}
+
+/// Generates an input for the given day, or `None` for days without a generator yet
+pub fn generate_input(day: u8, seed: u64, size: usize) -> Option<String> {
+    let input = match day {
+        1 => generate::<day1::CalibrationDocument>(seed, size).to_string(),
+        2 => generate::<day2::GameRecord>(seed, size).to_string(),
+        3 => generate::<day3::Schematic>(seed, size).to_string(),
+        4 => generate::<day4::Pile>(seed, size).to_string(),
+        5 => generate::<day5::Almanac>(seed, size).to_string(),
+        6 => generate::<day6::RaceSheet>(seed, size).to_string(),
+        7 => generate::<day7::HandList>(seed, size).to_string(),
+        8 => generate::<day8::Network>(seed, size).to_string(),
+        9 => generate::<day9::Report>(seed, size).to_string(),
+        10 => generate::<day10::PipeMaze>(seed, size).to_string(),
+        _ => return None,
+    };
+    Some(input)
+}
+
+/// Greedily shrinks `input` as long as `fails` holds, returning the smallest failing input found
+pub fn shrink<P: PuzzleInput>(mut input: P, mut fails: impl FnMut(&P) -> bool) -> P {
+    while let Some(smaller) = input
+        .shrink()
+        .into_iter()
+        .find(|candidate| fails(candidate))
+    {
+        input = smaller;
+    }
+    input
+}
+
+/// Candidates with one element removed at a time, keeping at least `min_len` elements
+fn removals<T: Clone>(items: &[T], min_len: usize) -> impl Iterator<Item = Vec<T>> + '_ {
+    (0..items.len())
+        .filter(move |_| items.len() > min_len)
+        .map(move |i| {
+            let mut smaller = items.to_vec();
+            smaller.remove(i);
+            smaller
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Numbers(Vec<u32>);
+
+    impl fmt::Display for Numbers {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+
+    impl PuzzleInput for Numbers {
+        fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+            Numbers((0..size).map(|_| rng.gen_range(0..100)).collect())
+        }
+
+        fn shrink(&self) -> Vec<Self> {
+            removals(&self.0, 1).map(Numbers).collect()
+        }
+    }
+
+    #[test]
+    fn generation_is_reproducible() {
+        assert_eq!(generate::<Numbers>(7, 10), generate::<Numbers>(7, 10));
+        assert_ne!(generate::<Numbers>(7, 10), generate::<Numbers>(8, 10));
+    }
+
+    #[test]
+    fn shrinks_to_minimal_failure() {
+        let input = Numbers(vec![3, 50, 7, 60, 1]);
+        let minimal = shrink(input, |numbers| {
+            numbers.0.iter().filter(|n| **n > 10).count() >= 2
+        });
+        assert_eq!(minimal, Numbers(vec![50, 60]));
+    }
+}
@@ -0,0 +1,66 @@
+use super::{removals, PuzzleInput};
+use rand::{seq::SliceRandom, Rng};
+use std::fmt;
+
+const DIGIT_WORDS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// Lines of letters, digits & spelled out digits, each with at least one digit from 1 to 9 so
+/// that both parts have a calibration value for every line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalibrationDocument {
+    pub lines: Vec<String>,
+}
+
+fn has_calibration_digit(line: &str) -> bool {
+    line.chars().any(|c| matches!(c, '1'..='9'))
+}
+
+impl fmt::Display for CalibrationDocument {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.lines.iter().try_for_each(|line| writeln!(f, "{line}"))
+    }
+}
+
+impl PuzzleInput for CalibrationDocument {
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        let lines = (0..rng.gen_range(1..=size.max(1)))
+            .map(|_| {
+                let mut line = String::new();
+                for _ in 0..rng.gen_range(1..=4 + size.min(20)) {
+                    match rng.gen_range(0..4) {
+                        0 => line.push(char::from(b'0' + rng.gen_range(0..10))),
+                        1 => line.push_str(DIGIT_WORDS.choose(rng).unwrap_or(&"one")),
+                        _ => line.push(char::from(rng.gen_range(b'a'..=b'z'))),
+                    }
+                }
+                if !has_calibration_digit(&line) {
+                    let index = rng.gen_range(0..=line.len());
+                    line.insert(index, char::from(b'0' + rng.gen_range(1..10)));
+                }
+                line
+            })
+            .collect();
+        CalibrationDocument { lines }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let fewer_lines = removals(&self.lines, 1).map(|lines| CalibrationDocument { lines });
+        let shorter_lines = self.lines.iter().enumerate().flat_map(|(i, line)| {
+            (0..line.len())
+                .map(move |removed| {
+                    let mut shorter = line.clone();
+                    shorter.remove(removed);
+                    shorter
+                })
+                .filter(|shorter| has_calibration_digit(shorter))
+                .map(move |shorter| {
+                    let mut lines = self.lines.clone();
+                    lines[i] = shorter;
+                    CalibrationDocument { lines }
+                })
+        });
+        fewer_lines.chain(shorter_lines).collect()
+    }
+}
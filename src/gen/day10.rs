@@ -0,0 +1,185 @@
+use super::PuzzleInput;
+use rand::{seq::IteratorRandom, seq::SliceRandom, Rng};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+type Coordinate = (i32, i32);
+
+const STEPS: [Coordinate; 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const JUNK: [char; 10] = ['|', '-', 'L', 'J', '7', 'F', '.', '.', '.', '.'];
+
+fn pipe_steps(tile: char) -> &'static [Coordinate] {
+    match tile {
+        '|' => &[(0, -1), (0, 1)],
+        '-' => &[(-1, 0), (1, 0)],
+        'L' => &[(0, -1), (1, 0)],
+        'J' => &[(0, -1), (-1, 0)],
+        '7' => &[(0, 1), (-1, 0)],
+        'F' => &[(0, 1), (1, 0)],
+        _ => &[],
+    }
+}
+
+fn pipe(steps: [Coordinate; 2]) -> char {
+    ['|', '-', 'L', 'J', '7', 'F']
+        .into_iter()
+        .find(|tile| steps.iter().all(|step| pipe_steps(*tile).contains(step)))
+        .expect("a loop only ever connects two different sides of a tile")
+}
+
+/// Map with a single loop going through the `S`, & junk pipes all around it that don't connect to
+/// the `S`, as the animal's pipe is inferred from the pipes connecting to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipeMaze {
+    pub tiles: Vec<Vec<char>>,
+    pub main_loop: BTreeSet<Coordinate>,
+}
+
+/// Whether a region of cells has no holes & no cells touching only diagonally, i.e. whether its
+/// boundary is a single loop that doesn't touch itself
+#[allow(clippy::cast_sign_loss)]
+fn is_simple(region: &BTreeSet<Coordinate>, (width, height): Coordinate) -> bool {
+    let inside = |cell: &Coordinate| region.contains(cell);
+    let no_diagonal_touch = (-1..height).all(|y| {
+        (-1..width).all(|x| {
+            let [a, b, c, d] = [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)].map(|c| inside(&c));
+            !(a == d && b == c && a != b)
+        })
+    });
+    // Every cell outside should be reachable from the border, so as to not be in a hole
+    let mut outside = BTreeSet::from([(-1, -1)]);
+    let mut pending = vec![(-1, -1)];
+    while let Some((x, y)) = pending.pop() {
+        for (dx, dy) in STEPS {
+            let next = (x + dx, y + dy);
+            if (-1..=width).contains(&next.0)
+                && (-1..=height).contains(&next.1)
+                && !inside(&next)
+                && outside.insert(next)
+            {
+                pending.push(next);
+            }
+        }
+    }
+    no_diagonal_touch && outside.len() + region.len() == ((width + 2) * (height + 2)) as usize
+}
+
+/// Steps along the loop from each tile on it, going around the boundary of a region of cells,
+/// where each cell spans 2 tiles in either direction
+fn boundary(region: &BTreeSet<Coordinate>) -> BTreeMap<Coordinate, Vec<Coordinate>> {
+    let mut steps: BTreeMap<Coordinate, Vec<Coordinate>> = BTreeMap::new();
+    let mut link = |from: Coordinate, (dx, dy): Coordinate| {
+        steps.entry(from).or_default().push((dx, dy));
+        steps
+            .entry((from.0 + dx, from.1 + dy))
+            .or_default()
+            .push((-dx, -dy));
+    };
+    for (x, y) in region {
+        let (left, top, right, bottom) = (2 * x, 2 * y, 2 * x + 2, 2 * y + 2);
+        for (neighbour, from, step) in [
+            ((x - 1, *y), (left, top), (0, 1)),
+            ((x + 1, *y), (right, top), (0, 1)),
+            ((*x, y - 1), (left, top), (1, 0)),
+            ((*x, y + 1), (left, bottom), (1, 0)),
+        ] {
+            if !region.contains(&neighbour) {
+                link(from, step);
+                link((from.0 + step.0, from.1 + step.1), step);
+            }
+        }
+    }
+    steps
+}
+
+impl fmt::Display for PipeMaze {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in &self.tiles {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+impl PuzzleInput for PipeMaze {
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        // Growing a region of cells one at a time, as long as its boundary stays a simple loop
+        let cells = (
+            rng.gen_range(1..=size.clamp(1, 12)) as i32,
+            rng.gen_range(1..=size.clamp(1, 12)) as i32,
+        );
+        let target = rng.gen_range(1..=(cells.0 * cells.1) as usize);
+        let mut region = BTreeSet::from([(rng.gen_range(0..cells.0), rng.gen_range(0..cells.1))]);
+        for _ in 0..20 * target {
+            if region.len() == target {
+                break;
+            }
+            let (x, y) = *region.iter().choose(rng).unwrap_or(&(0, 0));
+            let (dx, dy) = *STEPS.choose(rng).unwrap_or(&(1, 0));
+            let cell = (x + dx, y + dy);
+            if (0..cells.0).contains(&cell.0)
+                && (0..cells.1).contains(&cell.1)
+                && region.insert(cell)
+                && !is_simple(&region, cells)
+            {
+                region.remove(&cell);
+            }
+        }
+
+        let margin = (rng.gen_range(0..=2), rng.gen_range(0..=2));
+        let width = 2 * cells.0 + 1 + margin.0 + rng.gen_range(0..=2);
+        let height = 2 * cells.1 + 1 + margin.1 + rng.gen_range(0..=2);
+        let main_loop = boundary(&region)
+            .into_iter()
+            .map(|((x, y), steps)| ((x + margin.0, y + margin.1), steps))
+            .collect::<BTreeMap<_, _>>();
+        let mut tiles = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| match main_loop.get(&(x, y)) {
+                        Some(steps) => pipe([steps[0], steps[1]]),
+                        None => *JUNK.choose(rng).unwrap_or(&'.'),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let animal = *main_loop.keys().choose(rng).unwrap_or(&margin);
+        tiles[animal.1 as usize][animal.0 as usize] = 'S';
+        for (dx, dy) in STEPS {
+            let (x, y) = (animal.0 + dx, animal.1 + dy);
+            if (0..width).contains(&x)
+                && (0..height).contains(&y)
+                && !main_loop.contains_key(&(x, y))
+                && pipe_steps(tiles[y as usize][x as usize]).contains(&(-dx, -dy))
+            {
+                tiles[y as usize][x as usize] = '.';
+            }
+        }
+        PipeMaze {
+            tiles,
+            main_loop: main_loop.into_keys().collect(),
+        }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = Vec::new();
+        for (y, row) in self.tiles.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                if *tile != '.' && !self.main_loop.contains(&(x as i32, y as i32)) {
+                    let mut tiles = self.tiles.clone();
+                    tiles[y][x] = '.';
+                    candidates.push(PipeMaze {
+                        tiles,
+                        main_loop: self.main_loop.clone(),
+                    });
+                }
+            }
+        }
+        candidates
+    }
+}
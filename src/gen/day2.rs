@@ -0,0 +1,94 @@
+use super::{removals, PuzzleInput};
+use rand::{seq::SliceRandom, Rng};
+use std::fmt;
+
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+/// Cube counts by color, with each color shown at most once
+pub type Reveal = Vec<(&'static str, u32)>;
+
+/// Games numbered from 1, each with at least one reveal of at least one color
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub games: Vec<Vec<Reveal>>,
+}
+
+impl GameRecord {
+    fn is_valid(&self) -> bool {
+        !self.games.is_empty()
+            && self.games.iter().all(|reveals| {
+                !reveals.is_empty()
+                    && reveals.iter().all(|reveal| {
+                        !reveal.is_empty() && reveal.iter().all(|(_, count)| *count > 0)
+                    })
+            })
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (id, reveals) in (1..).zip(&self.games) {
+            let reveals = reveals
+                .iter()
+                .map(|reveal| {
+                    reveal
+                        .iter()
+                        .map(|(color, count)| format!("{count} {color}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .collect::<Vec<_>>();
+            writeln!(f, "Game {id}: {}", reveals.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl PuzzleInput for GameRecord {
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        // Up to 20 cubes of a color, so that some games are impossible with 12, 13 & 14 cubes
+        let games = (0..rng.gen_range(1..=size.max(1)))
+            .map(|_| {
+                (0..rng.gen_range(1..=6))
+                    .map(|_| {
+                        let amount = rng.gen_range(1..=COLORS.len());
+                        COLORS
+                            .choose_multiple(rng, amount)
+                            .map(|color| (*color, rng.gen_range(1..=20)))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        GameRecord { games }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let with_game = |i: usize, reveals: Vec<Reveal>| {
+            let mut games = self.games.clone();
+            games[i] = reveals;
+            GameRecord { games }
+        };
+        let mut candidates = removals(&self.games, 1)
+            .map(|games| GameRecord { games })
+            .collect::<Vec<_>>();
+        for (i, reveals) in self.games.iter().enumerate() {
+            candidates.extend(removals(reveals, 1).map(|reveals| with_game(i, reveals)));
+            for (j, reveal) in reveals.iter().enumerate() {
+                let mut smaller_reveals = removals(reveal, 1).collect::<Vec<_>>();
+                for (k, (color, count)) in reveal.iter().enumerate() {
+                    let mut smaller = reveal.clone();
+                    smaller[k] = (color, count / 2);
+                    smaller_reveals.push(smaller);
+                }
+                for smaller in smaller_reveals {
+                    let mut reveals = reveals.clone();
+                    reveals[j] = smaller;
+                    candidates.push(with_game(i, reveals));
+                }
+            }
+        }
+        candidates.retain(|candidate| candidate != self && candidate.is_valid());
+        candidates
+    }
+}
@@ -0,0 +1,77 @@
+use super::{removals, PuzzleInput};
+use rand::{seq::SliceRandom, Rng};
+use std::fmt;
+
+const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '/', '=', '%', '@', '&', '-'];
+
+/// Rectangular schematic of `.`s, symbols & numbers of up to 3 digits, like actual inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schematic {
+    pub rows: Vec<Vec<char>>,
+}
+
+impl Schematic {
+    fn is_valid(&self) -> bool {
+        self.rows.first().is_some_and(|first| {
+            !first.is_empty() && self.rows.iter().all(|row| row.len() == first.len())
+        })
+    }
+}
+
+impl fmt::Display for Schematic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in &self.rows {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+impl PuzzleInput for Schematic {
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        let width = rng.gen_range(1..=4 + 2 * size);
+        let rows = (0..rng.gen_range(1..=4 + 2 * size))
+            .map(|_| {
+                let mut row = Vec::with_capacity(width);
+                while row.len() < width {
+                    let after_number = row.last().is_some_and(char::is_ascii_digit);
+                    if !after_number && rng.gen_bool(0.3) {
+                        let digits = rng.gen_range(1..=3).min(width - row.len()) as u32;
+                        let number = rng.gen_range(10_u32.pow(digits - 1)..10_u32.pow(digits));
+                        row.extend(number.to_string().chars());
+                    } else if rng.gen_bool(0.2) {
+                        row.push(*SYMBOLS.choose(rng).unwrap_or(&'*'));
+                    } else {
+                        row.push('.');
+                    }
+                }
+                row
+            })
+            .collect();
+        Schematic { rows }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let width = self.rows.first().map_or(0, Vec::len);
+        let mut candidates = removals(&self.rows, 1)
+            .map(|rows| Schematic { rows })
+            .collect::<Vec<_>>();
+        for x in 0..width {
+            let rows = self
+                .rows
+                .iter()
+                .map(|row| [&row[..x], &row[x + 1..]].concat())
+                .collect();
+            candidates.push(Schematic { rows });
+        }
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, c)| **c != '.') {
+                let mut rows = self.rows.clone();
+                rows[y][x] = '.';
+                candidates.push(Schematic { rows });
+            }
+        }
+        candidates.retain(|candidate| candidate != self && candidate.is_valid());
+        candidates
+    }
+}
@@ -0,0 +1,112 @@
+use super::{removals, PuzzleInput};
+use rand::{seq::index, seq::SliceRandom, Rng};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScratchCard {
+    pub winning_numbers: Vec<u32>,
+    pub player_numbers: Vec<u32>,
+}
+
+impl ScratchCard {
+    fn matches(&self) -> usize {
+        self.player_numbers
+            .iter()
+            .filter(|num| self.winning_numbers.contains(num))
+            .count()
+    }
+}
+
+/// Cards numbered from 1, with distinct numbers on either side of each card, and no card winning
+/// copies of cards past the end of the table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pile {
+    pub cards: Vec<ScratchCard>,
+}
+
+impl Pile {
+    fn is_valid(&self) -> bool {
+        !self.cards.is_empty()
+            && self
+                .cards
+                .iter()
+                .enumerate()
+                .all(|(i, card)| i + card.matches() < self.cards.len())
+    }
+}
+
+impl fmt::Display for Pile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let id_width = self.cards.len().to_string().len();
+        let numbers = |numbers: &[u32]| {
+            numbers
+                .iter()
+                .map(|num| format!("{num:>2}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        for (id, card) in (1..).zip(&self.cards) {
+            writeln!(
+                f,
+                "Card {id:>id_width$}: {} | {}",
+                numbers(&card.winning_numbers),
+                numbers(&card.player_numbers)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl PuzzleInput for Pile {
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        // Every card has as many numbers as the others, like actual inputs (10 & 25)
+        let winning_count = rng.gen_range(1..=size.clamp(1, 10));
+        let player_count = winning_count + rng.gen_range(0..=size.min(15));
+        let card_count = rng.gen_range(1..=size.max(1));
+        let cards = (0..card_count)
+            .map(|i| {
+                let matches = rng.gen_range(0..=winning_count.min(card_count - 1 - i));
+                // Numbers from 1 to 99, the first ones being the winning numbers
+                let numbers = index::sample(rng, 99, winning_count + player_count - matches)
+                    .into_iter()
+                    .map(|num| num as u32 + 1)
+                    .collect::<Vec<_>>();
+                let (winning_numbers, others) = numbers.split_at(winning_count);
+                let mut player_numbers = [&winning_numbers[..matches], others].concat();
+                player_numbers.shuffle(rng);
+                ScratchCard {
+                    winning_numbers: winning_numbers.to_vec(),
+                    player_numbers,
+                }
+            })
+            .collect();
+        Pile { cards }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = removals(&self.cards, 1)
+            .map(|cards| Pile { cards })
+            .collect::<Vec<_>>();
+        for (i, card) in self.cards.iter().enumerate() {
+            let mut with_card = |card| {
+                let mut cards = self.cards.clone();
+                cards[i] = card;
+                candidates.push(Pile { cards });
+            };
+            removals(&card.winning_numbers, 1).for_each(|winning_numbers| {
+                with_card(ScratchCard {
+                    winning_numbers,
+                    player_numbers: card.player_numbers.clone(),
+                });
+            });
+            removals(&card.player_numbers, 1).for_each(|player_numbers| {
+                with_card(ScratchCard {
+                    winning_numbers: card.winning_numbers.clone(),
+                    player_numbers,
+                });
+            });
+        }
+        candidates.retain(|candidate| candidate != self && candidate.is_valid());
+        candidates
+    }
+}
@@ -0,0 +1,162 @@
+use super::{removals, PuzzleInput};
+use rand::{seq::index, Rng};
+use std::fmt;
+
+pub const CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeMapping {
+    pub destination: u64,
+    pub source: u64,
+    pub length: u64,
+}
+
+/// Seed ranges & a map between each pair of consecutive [`CATEGORIES`], with source ranges within
+/// a map never overlapping, and seed ranges short enough for brute force to go through them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanac {
+    pub seed_ranges: Vec<(u64, u64)>,
+    pub maps: Vec<Vec<RangeMapping>>,
+}
+
+impl Almanac {
+    fn is_valid(&self) -> bool {
+        !self.seed_ranges.is_empty()
+            && self.seed_ranges.iter().all(|(_, length)| *length > 0)
+            && self.maps.len() == CATEGORIES.len() - 1
+            && self.maps.iter().all(|map| {
+                map.iter().all(|mapping| mapping.length > 0)
+                    && map.iter().enumerate().all(|(i, this)| {
+                        map[i + 1..].iter().all(|other| {
+                            this.source + this.length <= other.source
+                                || other.source + other.length <= this.source
+                        })
+                    })
+            })
+    }
+}
+
+impl fmt::Display for Almanac {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "seeds:")?;
+        for (start, length) in &self.seed_ranges {
+            write!(f, " {start} {length}")?;
+        }
+        writeln!(f)?;
+        for (categories, map) in CATEGORIES.windows(2).zip(&self.maps) {
+            writeln!(f, "\n{}-to-{} map:", categories[0], categories[1])?;
+            for mapping in map {
+                writeln!(
+                    f,
+                    "{} {} {}",
+                    mapping.destination, mapping.source, mapping.length
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PuzzleInput for Almanac {
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        let size = size.max(1);
+        let span = 16 * size as u64 + 16;
+        let seed_ranges = (0..rng.gen_range(1..=size.min(4)))
+            .map(|_| {
+                (
+                    rng.gen_range(0..span),
+                    rng.gen_range(1..=2 * size as u64 + 1),
+                )
+            })
+            .collect();
+        let maps = (1..CATEGORIES.len())
+            .map(|_| {
+                let entries = rng.gen_range(1..=size.min(8));
+                // Mostly adjacent source ranges between distinct sorted points, as in actual
+                // inputs, with a few left out to leave gaps
+                let mut points = index::sample(rng, span as usize + 1, entries + 1).into_vec();
+                points.sort_unstable();
+                points
+                    .windows(2)
+                    .filter_map(|bounds| {
+                        if rng.gen_bool(0.2) {
+                            return None;
+                        }
+                        Some(RangeMapping {
+                            destination: rng.gen_range(0..span),
+                            source: bounds[0] as u64,
+                            length: (bounds[1] - bounds[0]) as u64,
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        Almanac { seed_ranges, maps }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = removals(&self.seed_ranges, 1)
+            .map(|seed_ranges| Almanac {
+                seed_ranges,
+                maps: self.maps.clone(),
+            })
+            .collect::<Vec<_>>();
+        for (i, (start, length)) in self.seed_ranges.iter().enumerate() {
+            for smaller in [(start / 2, *length), (*start, length / 2)] {
+                let mut seed_ranges = self.seed_ranges.clone();
+                seed_ranges[i] = smaller;
+                candidates.push(Almanac {
+                    seed_ranges,
+                    maps: self.maps.clone(),
+                });
+            }
+        }
+        for (i, map) in self.maps.iter().enumerate() {
+            let mut with_map = |map| {
+                let mut maps = self.maps.clone();
+                maps[i] = map;
+                candidates.push(Almanac {
+                    seed_ranges: self.seed_ranges.clone(),
+                    maps,
+                });
+            };
+            removals(map, 0).for_each(&mut with_map);
+            for (j, mapping) in map.iter().enumerate() {
+                let RangeMapping {
+                    destination,
+                    source,
+                    length,
+                } = *mapping;
+                for smaller in [
+                    RangeMapping {
+                        destination: destination / 2,
+                        ..*mapping
+                    },
+                    RangeMapping {
+                        source: source / 2,
+                        ..*mapping
+                    },
+                    RangeMapping {
+                        length: length / 2,
+                        ..*mapping
+                    },
+                ] {
+                    let mut map = map.clone();
+                    map[j] = smaller;
+                    with_map(map);
+                }
+            }
+        }
+        candidates.retain(|candidate| candidate != self && candidate.is_valid());
+        candidates
+    }
+}
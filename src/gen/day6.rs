@@ -0,0 +1,91 @@
+use super::{removals, PuzzleInput};
+use rand::Rng;
+use std::fmt;
+
+/// Records that can each be broken, as can the record formed by ignoring the spaces in between
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaceSheet {
+    pub races: Vec<(u64, u64)>,
+}
+
+/// Distance covered by holding the button for half the race, which is as far as a boat can go
+fn best_distance(time: u64) -> Option<u64> {
+    (time / 2).checked_mul(time - time / 2)
+}
+
+fn concatenated(numbers: impl Iterator<Item = u64>) -> Option<u64> {
+    numbers
+        .map(|num| num.to_string())
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+impl RaceSheet {
+    fn is_valid(&self) -> bool {
+        let breakable = |(time, distance)| best_distance(time).is_some_and(|best| distance < best);
+        !self.races.is_empty()
+            && self.races.iter().copied().all(breakable)
+            && concatenated(self.races.iter().map(|race| race.0))
+                .zip(concatenated(self.races.iter().map(|race| race.1)))
+                .is_some_and(breakable)
+    }
+}
+
+impl fmt::Display for RaceSheet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let widths = self
+            .races
+            .iter()
+            .map(|(time, distance)| time.to_string().len().max(distance.to_string().len()))
+            .collect::<Vec<_>>();
+        write!(f, "Time:    ")?;
+        for ((time, _), width) in self.races.iter().zip(&widths) {
+            write!(f, "  {time:>width$}")?;
+        }
+        write!(f, "\nDistance:")?;
+        for ((_, distance), width) in self.races.iter().zip(&widths) {
+            write!(f, "  {distance:>width$}")?;
+        }
+        writeln!(f)
+    }
+}
+
+impl PuzzleInput for RaceSheet {
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        let max_time = (6 + 4 * size as u64).min(99);
+        let times = (0..rng.gen_range(1..=size.clamp(1, 4)))
+            .map(|_| rng.gen_range(2..=max_time))
+            .collect::<Vec<_>>();
+        for _ in 0..10 {
+            let sheet = RaceSheet {
+                races: times
+                    .iter()
+                    .map(|time| (*time, rng.gen_range(0..best_distance(*time).unwrap_or(1))))
+                    .collect(),
+            };
+            if sheet.is_valid() {
+                return sheet;
+            }
+        }
+        // Zero can always be beaten, no matter how the races are read
+        RaceSheet {
+            races: times.into_iter().map(|time| (time, 0)).collect(),
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = removals(&self.races, 1)
+            .map(|races| RaceSheet { races })
+            .collect::<Vec<_>>();
+        for (i, (time, distance)) in self.races.iter().enumerate() {
+            for smaller in [(time / 2, *distance), (*time, distance / 2)] {
+                let mut races = self.races.clone();
+                races[i] = smaller;
+                candidates.push(RaceSheet { races });
+            }
+        }
+        candidates.retain(|candidate| candidate != self && candidate.is_valid());
+        candidates
+    }
+}
@@ -0,0 +1,72 @@
+use super::{removals, PuzzleInput};
+use rand::{seq::SliceRandom, Rng};
+use std::{collections::HashSet, fmt};
+
+const CARDS: [char; 13] = [
+    'A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2',
+];
+
+/// Distinct hands of 5 cards, each with a bid from 1 to 1000
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandList {
+    pub hands: Vec<(String, u32)>,
+}
+
+impl HandList {
+    fn is_valid(&self) -> bool {
+        let mut seen = HashSet::new();
+        !self.hands.is_empty()
+            && self
+                .hands
+                .iter()
+                .all(|(cards, bid)| *bid > 0 && seen.insert(cards))
+    }
+}
+
+impl fmt::Display for HandList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.hands
+            .iter()
+            .try_for_each(|(cards, bid)| writeln!(f, "{cards} {bid}"))
+    }
+}
+
+impl PuzzleInput for HandList {
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        let count = rng.gen_range(1..=size.max(1));
+        let mut seen = HashSet::new();
+        let mut hands = Vec::with_capacity(count);
+        // Drawing from a few kinds of cards at a time, as otherwise almost every hand is a high
+        // card or a pair. Giving up after a while, as there are only so many hands of one kind.
+        for _ in 0..10 * count {
+            if hands.len() == count {
+                break;
+            }
+            let kind_count = rng.gen_range(1..=5);
+            let kinds = CARDS
+                .choose_multiple(rng, kind_count)
+                .copied()
+                .collect::<Vec<_>>();
+            let cards = (0..5)
+                .map(|_| *kinds.choose(rng).unwrap_or(&'A'))
+                .collect::<String>();
+            if seen.insert(cards.clone()) {
+                hands.push((cards, rng.gen_range(1..=1000)));
+            }
+        }
+        HandList { hands }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = removals(&self.hands, 1)
+            .map(|hands| HandList { hands })
+            .collect::<Vec<_>>();
+        for (i, (cards, bid)) in self.hands.iter().enumerate() {
+            let mut hands = self.hands.clone();
+            hands[i] = (cards.clone(), bid / 2);
+            candidates.push(HandList { hands });
+        }
+        candidates.retain(|candidate| candidate != self && candidate.is_valid());
+        candidates
+    }
+}
@@ -0,0 +1,134 @@
+use super::{removals, PuzzleInput};
+use rand::Rng;
+use std::fmt;
+
+const GHOST_CHARS: &[u8] = b"A123456789";
+const NAME_CHARS: &[u8] = b"BCDEFGHIJKLMNOPQRSTUVWXY";
+const MAX_CYCLE: usize = NAME_CHARS.len() * NAME_CHARS.len() / 2;
+
+/// Network where each ghost walks from its `..A` node to its `..Z` node in `cycle` steps, and
+/// then comes back to the `..Z` node every `cycle` steps, whatever the instructions, as the LCM
+/// of the cycles only gives the answer for such networks. The first ghost walks from `AAA` to
+/// `ZZZ`, so that the network works for part 1 as well.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    pub instructions: String,
+    pub cycles: Vec<usize>,
+}
+
+impl Network {
+    fn is_valid(&self) -> bool {
+        !self.instructions.is_empty()
+            && (1..=GHOST_CHARS.len()).contains(&self.cycles.len())
+            && self
+                .cycles
+                .iter()
+                .all(|cycle| (1..=MAX_CYCLE).contains(cycle))
+    }
+}
+
+fn ghost_element(ghost: usize, last: char) -> String {
+    let ghost_char = char::from(GHOST_CHARS[ghost]);
+    if ghost == 0 {
+        last.to_string().repeat(3)
+    } else {
+        format!("{ghost_char}{ghost_char}{last}")
+    }
+}
+
+/// One of the two interchangeable elements a ghost can be at after `step` steps
+fn step_element(ghost: usize, step: usize, copy: usize) -> String {
+    let n = 2 * (step - 1) + copy;
+    [
+        GHOST_CHARS[ghost],
+        NAME_CHARS[n / NAME_CHARS.len()],
+        NAME_CHARS[n % NAME_CHARS.len()],
+    ]
+    .iter()
+    .map(|byte| char::from(*byte))
+    .collect()
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}\n", self.instructions)?;
+        for (ghost, cycle) in self.cycles.iter().copied().enumerate() {
+            let start = ghost_element(ghost, 'A');
+            let end = ghost_element(ghost, 'Z');
+            // Elements after taking a step from the given step, alternating which side goes where
+            let next = |step: usize| {
+                if step + 1 == cycle {
+                    (end.clone(), end.clone())
+                } else if (ghost + step).is_multiple_of(2) {
+                    (
+                        step_element(ghost, step + 1, 0),
+                        step_element(ghost, step + 1, 1),
+                    )
+                } else {
+                    (
+                        step_element(ghost, step + 1, 1),
+                        step_element(ghost, step + 1, 0),
+                    )
+                }
+            };
+            let (left, right) = next(0);
+            writeln!(f, "{start} = ({left}, {right})")?;
+            for step in 1..cycle {
+                let (left, right) = next(step);
+                for copy in 0..2 {
+                    let element = step_element(ghost, step, copy);
+                    writeln!(f, "{element} = ({left}, {right})")?;
+                }
+            }
+            // Back to where the start leads, so that the end is reached again after `cycle` steps
+            let (left, right) = next(0);
+            writeln!(f, "{end} = ({left}, {right})")?;
+        }
+        Ok(())
+    }
+}
+
+impl PuzzleInput for Network {
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        let instructions = (0..rng.gen_range(1..=size + 1))
+            .map(|_| if rng.gen_bool(0.5) { 'L' } else { 'R' })
+            .collect();
+        let max_cycle = (2 + 2 * size).min(MAX_CYCLE);
+        let cycles = (0..rng.gen_range(1..=size.clamp(1, GHOST_CHARS.len())))
+            .map(|_| rng.gen_range(1..=max_cycle))
+            .collect();
+        Network {
+            instructions,
+            cycles,
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = removals(&self.cycles, 1)
+            .map(|cycles| Network {
+                instructions: self.instructions.clone(),
+                cycles,
+            })
+            .collect::<Vec<_>>();
+        for (i, cycle) in self.cycles.iter().enumerate() {
+            for smaller in [cycle / 2, cycle - 1] {
+                let mut cycles = self.cycles.clone();
+                cycles[i] = smaller;
+                candidates.push(Network {
+                    instructions: self.instructions.clone(),
+                    cycles,
+                });
+            }
+        }
+        for i in 0..self.instructions.len() {
+            let mut instructions = self.instructions.clone();
+            instructions.remove(i);
+            candidates.push(Network {
+                instructions,
+                cycles: self.cycles.clone(),
+            });
+        }
+        candidates.retain(|candidate| candidate != self && candidate.is_valid());
+        candidates
+    }
+}
@@ -0,0 +1,72 @@
+use super::{removals, PuzzleInput};
+use rand::Rng;
+use std::fmt;
+
+/// Histories of polynomial sequences, of a degree low enough for the differences to reach zero
+/// before running out of numbers, as the extrapolation only makes sense for those
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub histories: Vec<Vec<i32>>,
+}
+
+/// Whether the differences reach all zeros while there are still some left
+fn is_polynomial(history: &[i32]) -> bool {
+    let mut diffs = history.to_vec();
+    while !diffs.is_empty() {
+        if diffs.iter().all(|diff| *diff == 0) {
+            return true;
+        }
+        diffs = diffs.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    }
+    false
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for history in &self.histories {
+            let history = history.iter().map(ToString::to_string).collect::<Vec<_>>();
+            writeln!(f, "{}", history.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl PuzzleInput for Report {
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        let histories = (0..rng.gen_range(1..=size.max(1)))
+            .map(|_| {
+                let len = rng.gen_range(2..=(4 + size).min(21));
+                let degree = rng.gen_range(0..=(len - 2).min(5));
+                // Going up from the constant differences, one starting number at a time
+                let mut history = vec![rng.gen_range(-10..=10); len];
+                for _ in 0..degree {
+                    let mut num = rng.gen_range(-20..=20);
+                    let mut sums = vec![num];
+                    for diff in &history[..len - 1] {
+                        num += diff;
+                        sums.push(num);
+                    }
+                    history = sums;
+                }
+                history
+            })
+            .collect();
+        Report { histories }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = removals(&self.histories, 1)
+            .map(|histories| Report { histories })
+            .collect::<Vec<_>>();
+        for (i, history) in self.histories.iter().enumerate() {
+            for shorter in [&history[1..], &history[..history.len() - 1]] {
+                if is_polynomial(shorter) {
+                    let mut histories = self.histories.clone();
+                    histories[i] = shorter.to_vec();
+                    candidates.push(Report { histories });
+                }
+            }
+        }
+        candidates
+    }
+}
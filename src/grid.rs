@@ -0,0 +1,313 @@
+//! A dense 2D grid of cells parsed from lines of text, for the puzzles whose input is a map
+
+use crate::parse::{self, Location};
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+/// Column & row of a cell, both starting at 0 from the top left
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Position {
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+    ];
+
+    /// Position one step away in this direction, unless that's off the top or left edge
+    pub fn step(self, Position { x, y }: Position) -> Option<Position> {
+        match self {
+            Direction::Up => Some(Position::new(x, y.checked_sub(1)?)),
+            Direction::Down => Some(Position::new(x, y + 1)),
+            Direction::Left => Some(Position::new(x.checked_sub(1)?, y)),
+            Direction::Right => Some(Position::new(x + 1, y)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError<E> {
+    EmptyLine {
+        location: Location,
+    },
+    RaggedLine {
+        location: Location,
+        expected_width: usize,
+        found_width: usize,
+    },
+    /// A character that the cell parser rejected
+    Cell(E),
+}
+
+impl<E: fmt::Display> fmt::Display for GridError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridError::EmptyLine { location } => {
+                write!(f, "{location}: expected a non-empty line")
+            }
+            GridError::RaggedLine {
+                location,
+                expected_width,
+                found_width,
+            } => write!(
+                f,
+                "{location}: expected {expected_width} characters like the first line, found {found_width}"
+            ),
+            GridError::Cell(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for GridError<E> {}
+
+/// Cells stored row after row, so that looking one up is just an index computation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses each character of each line of `input` into a cell, the lines all having to be
+    /// as wide as the first one. An empty input gives an empty grid.
+    pub fn parse<E>(
+        input: &str,
+        mut parse_cell: impl FnMut(char, Location) -> Result<T, E>,
+    ) -> Result<Self, GridError<E>> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for line in parse::lines(input) {
+            let found_width = line.text.chars().count();
+            if found_width == 0 {
+                return Err(GridError::EmptyLine {
+                    location: line.start(),
+                });
+            }
+            let expected_width = *width.get_or_insert(found_width);
+            if found_width != expected_width {
+                return Err(GridError::RaggedLine {
+                    location: line.end(),
+                    expected_width,
+                    found_width,
+                });
+            }
+            for (offset, char) in line.text.char_indices() {
+                cells.push(parse_cell(char, line.at_byte(offset)).map_err(GridError::Cell)?);
+            }
+            height += 1;
+        }
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    fn index_of(&self, Position { x, y }: Position) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index_of(position).map(|index| &mut self.cells[index])
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        self.index_of(position).is_some()
+    }
+
+    /// Position one step away in `direction`, unless that's off the grid
+    pub fn step(&self, position: Position, direction: Direction) -> Option<Position> {
+        direction.step(position).filter(|next| self.contains(*next))
+    }
+
+    /// Every position, row after row
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |index| Position::new(index % width, index / width))
+    }
+
+    /// Every cell along with its position, row after row
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Positions right above, below, left & right of `position` that are on the grid
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(position, direction))
+    }
+
+    /// Positions of the 8 cells surrounding `position`, diagonals included, that are on the grid
+    pub fn neighbours8(&self, Position { x, y }: Position) -> impl Iterator<Item = Position> + '_ {
+        let around = |coordinate: usize| coordinate.saturating_sub(1)..=coordinate + 1;
+        around(y)
+            .flat_map(move |ny| around(x).map(move |nx| Position::new(nx, ny)))
+            .filter(move |neighbour| *neighbour != Position::new(x, y) && self.contains(*neighbour))
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        self.get(position).unwrap_or_else(|| {
+            panic!(
+                "{position:?} is outside of the {}x{} grid",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{position:?} is outside of the {width}x{height} grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks(self.width.max(1)) {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::convert::Infallible;
+
+    fn digits(input: &str) -> Result<Grid<u32>, GridError<char>> {
+        Grid::parse(input, |char, _| char.to_digit(10).ok_or(char))
+    }
+
+    #[test]
+    fn parses_and_displays() {
+        let input = indoc! {"
+            123
+            456
+        "};
+        let grid = digits(input).expect("input should be a valid grid");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Position::new(2, 0)], 3);
+        assert_eq!(grid.get(Position::new(0, 1)), Some(&4));
+        assert_eq!(grid.get(Position::new(3, 0)), None);
+        assert_eq!(grid.get(Position::new(0, 2)), None);
+        assert_eq!(
+            grid.iter().map(|(_, cell)| *cell).collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(grid.to_string(), input);
+
+        let empty = Grid::parse("", |char, _| Ok::<_, Infallible>(char));
+        assert!(empty.is_ok_and(|grid| grid.is_empty() && grid.to_string().is_empty()));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            digits("123\n45\n").err(),
+            Some(GridError::RaggedLine {
+                location: Location { line: 2, column: 3 },
+                expected_width: 3,
+                found_width: 2,
+            })
+        );
+        assert_eq!(
+            digits("123\n\n456").err(),
+            Some(GridError::EmptyLine {
+                location: Location { line: 2, column: 1 },
+            })
+        );
+        assert_eq!(digits("123\n4x6").err(), Some(GridError::Cell('x')));
+    }
+
+    #[test]
+    fn neighbours_stay_on_the_grid() {
+        let mut grid = Grid::new(3, 3, '.');
+        grid[Position::new(1, 1)] = '#';
+        assert_eq!(grid.to_string(), "...\n.#.\n...\n");
+
+        let corner = Position::new(0, 0);
+        assert_eq!(
+            grid.neighbours4(corner).collect::<Vec<_>>(),
+            [Position::new(1, 0), Position::new(0, 1)]
+        );
+        assert_eq!(
+            grid.neighbours8(corner).collect::<Vec<_>>(),
+            [
+                Position::new(1, 0),
+                Position::new(0, 1),
+                Position::new(1, 1)
+            ]
+        );
+        assert_eq!(grid.neighbours4(Position::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(Position::new(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8(Position::new(2, 2)).count(), 3);
+
+        assert_eq!(grid.step(corner, Direction::Up), None);
+        assert_eq!(
+            grid.step(corner, Direction::Right),
+            Some(Position::new(1, 0))
+        );
+        assert_eq!(grid.step(Position::new(2, 0), Direction::Right), None);
+    }
+}
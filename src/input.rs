@@ -0,0 +1,372 @@
+//! Puzzle inputs read from a local cache, & fetched from adventofcode.com into it on a miss, as
+//! inputs can't be checked in
+//!
+//! Where the cache lives & how to fetch come from a [`Config`], which [`InputStore::from_config`]
+//! turns into a store.
+
+use serde::Deserialize;
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_CACHE_DIR: &str = "inputs";
+pub const DEFAULT_CONFIG_FILE: &str = "reindeer.toml";
+pub const CONFIG_FILE_ENV_VAR: &str = "REINDEER_CONFIG";
+pub const SESSION_ENV_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_ENV_VAR: &str = "AOC_BASE_URL";
+
+#[derive(Debug)]
+pub enum InputError {
+    Io { path: PathBuf, source: io::Error },
+    InvalidConfig { path: PathBuf, message: String },
+    NotCached { path: PathBuf },
+    MissingSession,
+    Http { url: String, status: u16 },
+    Transport { url: String, message: String },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            InputError::InvalidConfig { path, message } => {
+                write!(f, "{}: invalid config: {message}", path.display())
+            }
+            InputError::NotCached { path } => {
+                write!(f, "{} doesn't exist and no fetcher is configured", path.display())
+            }
+            InputError::MissingSession => write!(
+                f,
+                "no session cookie: set `session` in {DEFAULT_CONFIG_FILE} or the {SESSION_ENV_VAR} env var"
+            ),
+            InputError::Http { url, status } => write!(f, "{url}: server responded with {status}"),
+            InputError::Transport { url, message } => write!(f, "{url}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Settings for where inputs are cached & fetched from, read from [`DEFAULT_CONFIG_FILE`]
+/// (or the file at [`CONFIG_FILE_ENV_VAR`]), with env vars taking precedence:
+///
+/// ```toml
+/// session = "53616c7465645f5f..."   # or AOC_SESSION
+/// base_url = "https://adventofcode.com" # or AOC_BASE_URL
+/// cache_dir = "inputs"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+    pub cache_dir: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            cache_dir: PathBuf::from(DEFAULT_CACHE_DIR),
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self, InputError> {
+        let path = env::var_os(CONFIG_FILE_ENV_VAR)
+            .map_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE), PathBuf::from);
+        let config = if path.exists() {
+            Self::from_file(&path)?
+        } else {
+            Self::default()
+        };
+        Ok(config.with_overrides(|name| env::var(name).ok()))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, InputError> {
+        let contents = fs::read_to_string(path).map_err(|source| InputError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&contents).map_err(|error| InputError::InvalidConfig {
+            path: path.to_path_buf(),
+            message: error.message().to_string(),
+        })
+    }
+
+    /// Overrides settings with the values `lookup` finds for their env vars
+    #[must_use]
+    pub fn with_overrides(mut self, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(session) = lookup(SESSION_ENV_VAR) {
+            self.session = Some(session);
+        }
+        if let Some(base_url) = lookup(BASE_URL_ENV_VAR) {
+            self.base_url = base_url;
+        }
+        self
+    }
+}
+
+/// Source of inputs that aren't in the local cache yet
+pub trait Fetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, InputError>;
+}
+
+/// Fetches inputs from `<base_url>/<year>/day/<day>/input`, authenticated by the session cookie
+pub struct HttpFetcher {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl HttpFetcher {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            agent: ureq::AgentBuilder::new()
+                .user_agent("github.com/aksh1618/rusted-reindeer")
+                .build(),
+        }
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, InputError> {
+        let url = format!("{}/{year}/day/{day}/input", self.base_url);
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()
+            .map_err(|error| match error {
+                ureq::Error::Status(status, _) => InputError::Http {
+                    url: url.clone(),
+                    status,
+                },
+                ureq::Error::Transport(transport) => InputError::Transport {
+                    url: url.clone(),
+                    message: transport.to_string(),
+                },
+            })?;
+        response
+            .into_string()
+            .map_err(|error| InputError::Transport {
+                url,
+                message: error.to_string(),
+            })
+    }
+}
+
+/// Stands in for the fetcher when no session is configured, so that a cache miss says to set one
+struct NoSession;
+
+impl Fetcher for NoSession {
+    fn fetch(&self, _year: u16, _day: u8) -> Result<String, InputError> {
+        Err(InputError::MissingSession)
+    }
+}
+
+/// Inputs cached as `<cache_dir>/<year>/day<day>.txt`, with cache misses going to the fetcher
+pub struct InputStore {
+    cache_dir: PathBuf,
+    fetcher: Option<Box<dyn Fetcher>>,
+}
+
+impl InputStore {
+    pub fn new(cache_dir: impl Into<PathBuf>, fetcher: Box<dyn Fetcher>) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            fetcher: Some(fetcher),
+        }
+    }
+
+    /// A store that only ever reads from the cache
+    pub fn offline(cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            fetcher: None,
+        }
+    }
+
+    /// Fetches over HTTP if a session is configured, otherwise only reads from the cache, failing
+    /// with [`InputError::MissingSession`] on a miss
+    pub fn from_config(config: &Config) -> Self {
+        match &config.session {
+            Some(session) => Self::new(
+                &config.cache_dir,
+                Box::new(HttpFetcher::new(&config.base_url, session)),
+            ),
+            None => Self::new(&config.cache_dir, Box::new(NoSession)),
+        }
+    }
+
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.cache_dir
+            .join(year.to_string())
+            .join(format!("day{day}.txt"))
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Result<String, InputError> {
+        let path = self.path(year, day);
+        match fs::read_to_string(&path) {
+            Ok(input) => return Ok(input),
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                return Err(InputError::Io {
+                    path,
+                    source: error,
+                })
+            }
+            Err(_) => (),
+        }
+        let Some(fetcher) = &self.fetcher else {
+            return Err(InputError::NotCached { path });
+        };
+        let input = fetcher.fetch(year, day)?;
+        let io_error = |source| InputError::Io {
+            path: path.clone(),
+            source,
+        };
+        if let Some(year_dir) = path.parent() {
+            fs::create_dir_all(year_dir).map_err(io_error)?;
+        }
+        fs::write(&path, &input).map_err(io_error)?;
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    /// Stands in for adventofcode.com: answers a single request, reporting its request line & cookie
+    fn serve_once(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, mpsc::Receiver<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("should bind to a free local port");
+        let base_url = format!(
+            "http://{}",
+            listener
+                .local_addr()
+                .expect("listener should have an address")
+        );
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("client should connect");
+            let request = BufReader::new(&stream)
+                .lines()
+                .map_while(Result::ok)
+                .take_while(|line| !line.is_empty())
+                .filter(|line| line.starts_with("GET ") || line.starts_with("Cookie: "))
+                .collect();
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .expect("response should be written");
+            sender.send(request).expect("test should be waiting");
+        });
+        (base_url, receiver)
+    }
+
+    #[test]
+    fn reads_cached_input_without_fetching() {
+        let cache_dir = tempfile::tempdir().expect("temp dir should be created");
+        let store = InputStore::offline(cache_dir.path());
+        assert!(matches!(
+            store.get(2023, 1),
+            Err(InputError::NotCached { .. })
+        ));
+        fs::create_dir_all(cache_dir.path().join("2023")).expect("year dir should be created");
+        fs::write(store.path(2023, 1), "1abc2\n").expect("input should be written");
+        assert_eq!(store.get(2023, 1).ok(), Some("1abc2\n".to_string()));
+    }
+
+    #[test]
+    fn missing_session_is_reported_on_a_miss() {
+        let cache_dir = tempfile::tempdir().expect("temp dir should be created");
+        let config = Config {
+            cache_dir: cache_dir.path().to_path_buf(),
+            ..Config::default()
+        };
+        let store = InputStore::from_config(&config);
+        assert!(matches!(
+            store.get(2023, 1),
+            Err(InputError::MissingSession)
+        ));
+        assert!(!store.path(2023, 1).exists());
+    }
+
+    #[test]
+    fn fetches_and_caches_missing_input() {
+        let (base_url, requests) = serve_once("200 OK", "Time: 7\nDistance: 9\n");
+        let cache_dir = tempfile::tempdir().expect("temp dir should be created");
+        let config = Config {
+            session: Some("cafe".to_string()),
+            base_url,
+            cache_dir: cache_dir.path().to_path_buf(),
+        };
+        let store = InputStore::from_config(&config);
+        assert_eq!(
+            store.get(2023, 6).ok(),
+            Some("Time: 7\nDistance: 9\n".to_string())
+        );
+        assert_eq!(
+            requests.recv().ok(),
+            Some(vec![
+                "GET /2023/day/6/input HTTP/1.1".to_string(),
+                "Cookie: session=cafe".to_string(),
+            ])
+        );
+        // Served from the cache now, as the stand-in server has shut down
+        assert_eq!(
+            fs::read_to_string(cache_dir.path().join("2023").join("day6.txt")).ok(),
+            store.get(2023, 6).ok()
+        );
+    }
+
+    #[test]
+    fn failed_fetch_is_not_cached() {
+        let (base_url, _requests) = serve_once("400 Bad Request", "Please log in");
+        let cache_dir = tempfile::tempdir().expect("temp dir should be created");
+        let store = InputStore::new(
+            cache_dir.path(),
+            Box::new(HttpFetcher::new(&base_url, "stale")),
+        );
+        assert!(matches!(
+            store.get(2023, 5),
+            Err(InputError::Http { status: 400, .. })
+        ));
+        assert!(!store.path(2023, 5).exists());
+    }
+
+    #[test]
+    fn env_vars_override_config_file() {
+        let config: Config =
+            toml::from_str(r#"session = "from-file""#).expect("config should parse");
+        assert_eq!(config.base_url, DEFAULT_BASE_URL);
+        let config =
+            config.with_overrides(|name| (name == SESSION_ENV_VAR).then(|| "from-env".to_string()));
+        assert_eq!(config.session.as_deref(), Some("from-env"));
+        assert_eq!(config.cache_dir, PathBuf::from(DEFAULT_CACHE_DIR));
+    }
+}
@@ -0,0 +1,29 @@
+//! Solutions to the Advent of Code 2023 puzzles, one module per day, along with the tooling around
+//! them: fetching & caching inputs, registering accepted answers, generating random inputs and
+//! keeping a history of benchmarks.
+//!
+//! The binaries that run the solutions live in the `cli` crate of the workspace.
+
+pub mod answers;
+pub mod bench;
+pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+#[cfg(test)]
+mod differential;
+pub mod gen;
+pub mod grid;
+pub mod input;
+pub mod parse;
+pub mod partition_point;
+pub mod range_set;
+pub mod solution;
+#[cfg(test)]
+mod verify;
@@ -0,0 +1,95 @@
+use std::fmt;
+
+/// Position of a character in the puzzle input, both line & column starting at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A line of the puzzle input that remembers where it came from, so that errors
+/// about any of its parts can point at the exact location in the input
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    index: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(index: usize, text: &'a str) -> Self {
+        Self { index, text }
+    }
+
+    /// Location of `part`, which should be a slice of this line's text
+    pub fn locate(&self, part: &str) -> Location {
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(|offset| *offset <= self.text.len())
+            .unwrap_or(0);
+        self.at_byte(offset)
+    }
+
+    /// Location of the byte at `offset` in this line's text
+    pub fn at_byte(&self, offset: usize) -> Location {
+        let column = self
+            .text
+            .get(..offset)
+            .map_or(offset, |preceding| preceding.chars().count())
+            + 1;
+        Location {
+            line: self.index + 1,
+            column,
+        }
+    }
+
+    pub fn start(&self) -> Location {
+        self.at_byte(0)
+    }
+
+    /// Location just past the last character, for when something is missing at the end
+    pub fn end(&self) -> Location {
+        self.at_byte(self.text.len())
+    }
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, text)| Line::new(index, text))
+}
+
+/// Location just past the end of the input, for when it is truncated
+pub fn end_of_input(input: &str) -> Location {
+    Location {
+        line: input.lines().count() + 1,
+        column: 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_part_of_line() {
+        let input = "Game 1: 3 blue\nGame 2: 4 red";
+        let line = lines(input).nth(1).expect("input should have two lines");
+        let part = &line.text[8..];
+        assert_eq!(line.locate(part), Location { line: 2, column: 9 });
+        assert_eq!(
+            line.end(),
+            Location {
+                line: 2,
+                column: 14
+            }
+        );
+        assert_eq!(end_of_input(input), Location { line: 3, column: 1 });
+    }
+}
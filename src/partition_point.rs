@@ -0,0 +1,294 @@
+//! Binary search for the point where a predicate over a range of integers changes, for the puzzles
+//! whose answer is where a condition starts or stops holding
+
+use std::{
+    fmt,
+    ops::{Range, RangeInclusive},
+};
+
+/// Points of the range checked against the search result by
+/// [`PartitionPoint::verified_partition_point`]
+const MONOTONICITY_SAMPLES: u128 = 16;
+
+/// Primitive integers, which get mapped to `u128` keys in the same order so that a single search
+/// covers them all without overflowing
+pub trait Integer: Copy + fmt::Debug {
+    fn to_key(self) -> u128;
+    /// Inverse of [`Integer::to_key`], only called with keys of values of this type
+    fn from_key(key: u128) -> Self;
+}
+
+macro_rules! unsigned_integer {
+    ($($type:ty),*) => {$(
+        impl Integer for $type {
+            #[allow(clippy::cast_lossless)] // `usize` has no `From` impl to `u128`
+            fn to_key(self) -> u128 {
+                self as u128
+            }
+
+            fn from_key(key: u128) -> Self {
+                key as $type
+            }
+        }
+    )*};
+}
+
+// Flipping the sign bit of the two's complement moves the negative numbers below the others
+macro_rules! signed_integer {
+    ($($type:ty),*) => {$(
+        impl Integer for $type {
+            #[allow(clippy::cast_lossless, clippy::cast_sign_loss)] // As for unsigned integers
+            fn to_key(self) -> u128 {
+                (self as i128 as u128) ^ (1 << 127)
+            }
+
+            #[allow(clippy::cast_possible_wrap)]
+            fn from_key(key: u128) -> Self {
+                (key ^ (1 << 127)) as i128 as $type
+            }
+        }
+    )*};
+}
+
+unsigned_integer!(u8, u16, u32, u64, u128, usize);
+signed_integer!(i8, i16, i32, i64, i128, isize);
+
+/// Binary search for the first point where `pred` no longer gives what it gives at the start, like
+/// [`slice::partition_point`], but without requiring `pred` to hold at the start
+///
+/// `pred` has to change at most once over the range, which
+/// [`verified_partition_point`](PartitionPoint::verified_partition_point) checks on a sample of
+/// points, for when it's worth a few more calls of `pred`.
+///
+/// ```rust
+/// # use rusted_reindeer::partition_point::PartitionPoint;
+/// assert_eq!((0..=10_u64).partition_point(|num| *num < 4), 4);
+/// assert_eq!((0..=10_u64).partition_point(|num| *num >= 4), 4);
+/// assert_eq!((-10..10_i8).checked_partition_point(|num| *num < 4), Some(4));
+/// assert_eq!((-10..10_i8).checked_partition_point(|num| *num < 40), None);
+/// assert!((0..=10_u64).verified_partition_point(|num| *num % 2 == 0).is_err());
+/// ```
+pub trait PartitionPoint<T> {
+    /// The point where `pred` changes, or the end of the range if it doesn't
+    fn partition_point<P>(&self, pred: P) -> T
+    where
+        P: FnMut(&T) -> bool;
+
+    /// The point where `pred` changes, if it does
+    fn checked_partition_point<P>(&self, pred: P) -> Option<T>
+    where
+        P: FnMut(&T) -> bool;
+
+    /// Like [`checked_partition_point`](PartitionPoint::checked_partition_point), but failing if
+    /// `pred` turns out to change more than once, going by evenly spread points of the range along
+    /// with those right around the point found
+    fn verified_partition_point<P>(&self, pred: P) -> Result<Option<T>, MonotonicityError<T>>
+    where
+        P: FnMut(&T) -> bool;
+}
+
+/// `pred` gives `start_state` at `start`, then the opposite at `point`, but `found` at `key`, which
+/// goes against that
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonotonicityError<T> {
+    pub start: T,
+    pub start_state: bool,
+    pub point: Option<T>,
+    pub key: T,
+    pub found: bool,
+}
+
+impl<T: fmt::Debug> fmt::Display for MonotonicityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the predicate isn't monotonic: it gives {} at {:?}, then {} at {:?}, but {} at {:?}",
+            self.start_state, self.start, !self.start_state, self.point, self.found, self.key,
+        )
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for MonotonicityError<T> {}
+
+impl<T: Integer> PartitionPoint<T> for RangeInclusive<T> {
+    fn partition_point<P>(&self, pred: P) -> T
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.checked_partition_point(pred).unwrap_or(*self.end())
+    }
+
+    fn checked_partition_point<P>(&self, pred: P) -> Option<T>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        search(self.start().to_key(), self.end().to_key(), pred)
+    }
+
+    fn verified_partition_point<P>(&self, pred: P) -> Result<Option<T>, MonotonicityError<T>>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        verified_search(self.start().to_key(), self.end().to_key(), pred)
+    }
+}
+
+impl<T: Integer> PartitionPoint<T> for Range<T> {
+    fn partition_point<P>(&self, pred: P) -> T
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.checked_partition_point(pred).unwrap_or(self.end)
+    }
+
+    fn checked_partition_point<P>(&self, pred: P) -> Option<T>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let last = self.end.to_key().checked_sub(1)?;
+        search(self.start.to_key(), last, pred)
+    }
+
+    fn verified_partition_point<P>(&self, pred: P) -> Result<Option<T>, MonotonicityError<T>>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let Some(last) = self.end.to_key().checked_sub(1) else {
+            return Ok(None);
+        };
+        verified_search(self.start.to_key(), last, pred)
+    }
+}
+
+fn search<T: Integer>(start: u128, last: u128, mut pred: impl FnMut(&T) -> bool) -> Option<T> {
+    search_keys(start, last, &mut |key| pred(&T::from_key(key))).map(T::from_key)
+}
+
+fn verified_search<T: Integer>(
+    start: u128,
+    last: u128,
+    mut pred: impl FnMut(&T) -> bool,
+) -> Result<Option<T>, MonotonicityError<T>> {
+    let mut pred = |key| pred(&T::from_key(key));
+    let point = search_keys(start, last, &mut pred);
+    check_monotonic(start, last, point, &mut pred)?;
+    Ok(point.map(T::from_key))
+}
+
+/// The first key from `start` to `last` at which `pred` gives something else than at `start`
+fn search_keys(start: u128, last: u128, pred: &mut impl FnMut(u128) -> bool) -> Option<u128> {
+    if start > last {
+        return None;
+    }
+    let start_state = pred(start);
+    let (mut low, mut high) = (start, last);
+    while low < high {
+        // Can't overflow, unlike (low + high) / 2
+        let mid = low + (high - low) / 2;
+        if pred(mid) == start_state {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    (pred(low) != start_state).then_some(low)
+}
+
+/// Checks that `pred` gives what it gives at `start` before `point` & the opposite from it on, at
+/// evenly spread points of the range along with those right around `point`
+fn check_monotonic<T: Integer>(
+    start: u128,
+    last: u128,
+    point: Option<u128>,
+    pred: &mut impl FnMut(u128) -> bool,
+) -> Result<(), MonotonicityError<T>> {
+    if start > last {
+        return Ok(());
+    }
+    let start_state = pred(start);
+    let step = ((last - start) / MONOTONICITY_SAMPLES).max(1);
+    let spread = (0..=MONOTONICITY_SAMPLES).filter_map(|sample| {
+        let key = start.checked_add(step.checked_mul(sample)?)?;
+        (key <= last).then_some(key)
+    });
+    let around_point = point.into_iter().flat_map(|point| [point - 1, point]);
+    for key in spread.chain([last]).chain(around_point) {
+        let expected = point.is_none_or(|point| key < point) == start_state;
+        if pred(key) != expected {
+            return Err(MonotonicityError {
+                start: T::from_key(start),
+                start_state,
+                point: point.map(T::from_key),
+                key: T::from_key(key),
+                found: !expected,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn searches_every_integer_type() {
+        assert_eq!((0..=u8::MAX).partition_point(|num| *num < 200), 200);
+        assert_eq!((i8::MIN..=i8::MAX).partition_point(|num| *num < -100), -100);
+        assert_eq!((i16::MIN..0).partition_point(|num| *num <= -2), -1);
+        assert_eq!((1..u32::MAX).partition_point(|num| *num > 7), 8);
+        assert_eq!(
+            (u64::MAX - 10..=u64::MAX).partition_point(|num| *num < u64::MAX - 3),
+            u64::MAX - 3
+        );
+        assert_eq!(
+            (0..=u128::MAX).partition_point(|num| *num < u128::MAX),
+            u128::MAX
+        );
+        assert_eq!(
+            (i128::MIN..=i128::MAX).partition_point(|num| *num < i128::MIN + 1),
+            i128::MIN + 1
+        );
+        assert_eq!((-5_isize..5).partition_point(|num| *num < 0), 0);
+        assert_eq!((0_usize..5).partition_point(|num| *num < 9), 5);
+    }
+
+    #[test]
+    fn tells_when_there_is_no_transition() {
+        assert_eq!((0..10_u16).checked_partition_point(|num| *num < 20), None);
+        assert_eq!((0..10_u16).partition_point(|num| *num < 20), 10);
+        assert_eq!((0..=10_i64).partition_point(|num| *num > -1), 10);
+        assert_eq!((5..5_i32).checked_partition_point(|_| true), None);
+        #[allow(clippy::reversed_empty_ranges)] // Checking that empty ranges have no transition
+        let empty = 5..=3_u8;
+        assert_eq!(empty.checked_partition_point(|_| true), None);
+        assert_eq!((0..=0_u8).checked_partition_point(|_| true), None);
+        assert_eq!((0..=1_u8).checked_partition_point(|num| *num == 0), Some(1));
+    }
+
+    #[test]
+    fn detects_non_monotonic_predicates() {
+        let error = (0..100_u32)
+            .verified_partition_point(|num| *num < 10 || *num > 90)
+            .expect_err("the predicate changes twice");
+        assert_eq!(
+            (error.start, error.start_state, error.point),
+            (0, true, Some(10))
+        );
+        assert!(error.key > 90 && error.found);
+        assert_eq!(
+            (0..100_u32).verified_partition_point(|num| *num < 10),
+            Ok(Some(10))
+        );
+        assert_eq!(
+            (i8::MIN..=i8::MAX).verified_partition_point(|num| *num < 0),
+            Ok(Some(0))
+        );
+        assert_eq!((5..5_u8).verified_partition_point(|_| true), Ok(None));
+        // Unverified searches trust the predicate
+        assert_eq!(
+            (0..100_u32).checked_partition_point(|num| *num < 10 || *num > 90),
+            Some(10)
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Sets of numbers stored as ranges, for puzzles that deal with more numbers than can be listed
+
+use std::ops::RangeInclusive;
+
+/// Numbers stored as sorted ranges that neither overlap nor touch, so that each set has a single
+/// minimal representation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    ranges: Vec<RangeInclusive<u64>>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[RangeInclusive<u64>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many numbers are in the set, which takes more than a `u64` for the full range
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|range| u128::from(range.end() - range.start()) + 1)
+            .sum()
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.ranges.first().map(|range| *range.start())
+    }
+
+    pub fn contains(&self, number: u64) -> bool {
+        let index = self.ranges.partition_point(|range| *range.end() < number);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.contains(&number))
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).cloned().collect()
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut ours, mut theirs) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(our_range), Some(their_range)) = (ours.peek(), theirs.peek()) {
+            let start = *our_range.start().max(their_range.start());
+            let end = *our_range.end().min(their_range.end());
+            if start <= end {
+                ranges.push(start..=end);
+            }
+            // The range ending first can't overlap anything past the other one
+            if our_range.end() < their_range.end() {
+                ours.next();
+            } else {
+                theirs.next();
+            }
+        }
+        // Pieces of ranges that don't touch can't touch either
+        Self { ranges }
+    }
+
+    /// Numbers of this set that aren't in `other`
+    ///
+    /// A vizualization: (`|` indicates start & end of a range)
+    /// ```text
+    /// This set    ->  |*********************|   |****|
+    /// Other set   ->  |###|-------|##||#|-------|#|---
+    /// Difference  ->       |#####|       |##|      |#|
+    /// ```
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut theirs = other.ranges.iter().peekable();
+        for range in &self.ranges {
+            let mut start = Some(*range.start());
+            // Ranges of the other set ending before this one can't overlap the ones after it either
+            while theirs
+                .next_if(|removed| removed.end() < range.start())
+                .is_some()
+            {}
+            for removed in theirs.clone() {
+                let Some(remaining_start) = start.filter(|start| start <= range.end()) else {
+                    break;
+                };
+                if removed.start() > range.end() {
+                    break;
+                }
+                if *removed.start() > remaining_start {
+                    ranges.push(remaining_start..=removed.start() - 1);
+                }
+                start = removed.end().checked_add(1);
+            }
+            if let Some(start) = start.filter(|start| start <= range.end()) {
+                ranges.push(start..=*range.end());
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Every number moved by `offset`, or `None` if that would take one out of the `u64` range
+    pub fn shift(&self, offset: i128) -> Option<Self> {
+        let shift = |number: u64| u64::try_from(i128::from(number) + offset).ok();
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|range| Some(shift(*range.start())?..=shift(*range.end())?))
+            .collect::<Option<_>>()?;
+        Some(Self { ranges })
+    }
+}
+
+impl From<RangeInclusive<u64>> for RangeSet {
+    fn from(range: RangeInclusive<u64>) -> Self {
+        Some(range).into_iter().collect()
+    }
+}
+
+/// Coalesces ranges that overlap or touch, leaving out empty ones
+impl FromIterator<RangeInclusive<u64>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<u64>>>(ranges: I) -> Self {
+        let mut sorted = ranges
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .collect::<Vec<_>>();
+        sorted.sort_unstable_by_key(|range| *range.start());
+        let mut ranges: Vec<RangeInclusive<u64>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match ranges.last_mut() {
+                Some(last)
+                    if last
+                        .end()
+                        .checked_add(1)
+                        .is_none_or(|after| *range.start() <= after) =>
+                {
+                    *last = *last.start()..=*last.end().max(range.end());
+                }
+                _ => ranges.push(range),
+            }
+        }
+        Self { ranges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set<const N: usize>(ranges: [RangeInclusive<u64>; N]) -> RangeSet {
+        ranges.into_iter().collect()
+    }
+
+    #[test]
+    fn coalesces_overlapping_and_touching_ranges() {
+        #[allow(clippy::reversed_empty_ranges)] // Checking that empty ranges are left out
+        let coalesced = set([
+            41..=44,
+            28..=34,
+            50..=53,
+            25..=29,
+            41..=43,
+            35..=35,
+            60..=59,
+        ]);
+        assert_eq!(coalesced.ranges(), [25..=35, 41..=44, 50..=53]);
+        assert_eq!(
+            set([u64::MAX..=u64::MAX, 0..=u64::MAX - 1]).ranges(),
+            [0..=u64::MAX]
+        );
+        assert_eq!(coalesced.min(), Some(25));
+        assert_eq!(coalesced.len(), 19);
+        assert_eq!(set([0..=u64::MAX]).len(), u128::from(u64::MAX) + 1);
+        assert!(coalesced.contains(35) && coalesced.contains(41) && !coalesced.contains(40));
+        assert!(RangeSet::new().is_empty() && RangeSet::new().min().is_none());
+        assert_eq!(RangeSet::new().len(), 0);
+    }
+
+    #[test]
+    fn set_operations() {
+        let this = set([0..=21, 25..=30]);
+        let other = set([0..=3, 11..=13, 16..=16, 26..=26]);
+        assert_eq!(
+            this.difference(&other).ranges(),
+            [4..=10, 14..=15, 17..=21, 25..=25, 27..=30]
+        );
+        assert_eq!(
+            this.intersection(&other).ranges(),
+            [0..=3, 11..=13, 16..=16, 26..=26]
+        );
+        assert_eq!(
+            this.union(&set([22..=24, 40..=41])).ranges(),
+            [0..=30, 40..=41]
+        );
+        assert_eq!(this.difference(&this), RangeSet::new());
+        assert_eq!(
+            set([0..=5, 10..=15, 20..=25, 30..=31])
+                .difference(&set([3..=22, 24..=24, 40..=41]))
+                .ranges(),
+            [0..=2, 23..=23, 25..=25, 30..=31]
+        );
+        assert_eq!(
+            set([0..=u64::MAX])
+                .difference(&set([10..=u64::MAX]))
+                .ranges(),
+            [0..=9]
+        );
+        assert_eq!(
+            set([5..=u64::MAX])
+                .intersection(&set([0..=6, 9..=u64::MAX]))
+                .ranges(),
+            [5..=6, 9..=u64::MAX]
+        );
+    }
+
+    #[test]
+    fn shifts_within_the_u64_range() {
+        let numbers = set([10..=20, 30..=40]);
+        assert_eq!(
+            numbers.shift(-10).map(|set| set.ranges().to_vec()),
+            Some(vec![0..=10, 20..=30])
+        );
+        assert_eq!(numbers.shift(-11), None);
+        assert_eq!(
+            numbers
+                .shift(i128::from(u64::MAX - 40))
+                .and_then(|set| set.min()),
+            Some(u64::MAX - 30)
+        );
+        assert_eq!(numbers.shift(i128::from(u64::MAX - 39)), None);
+    }
+}
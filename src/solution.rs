@@ -0,0 +1,267 @@
+//! A common interface over every day's solutions, & a registry to enumerate & call them at runtime,
+//! which is what the `cli` binaries run them through
+
+use crate::{answers::VariantAnswers, day1, day10, day2, day3, day4, day5, day6, day7, day8, day9};
+use std::fmt;
+
+/// Answer to a part, or why the input couldn't be solved
+pub type PartResult<S> = Result<<S as Solution>::Answer, <S as Solution>::Error>;
+
+/// One way of solving a part of a puzzle
+pub struct Variant<S: Solution> {
+    /// Name of the function implementing it, like `part1_binary`
+    pub name: &'static str,
+    pub solve: fn(&S::Parsed) -> PartResult<S>,
+    /// Brute force variants that only complete for small inputs, like generated ones
+    pub slow: bool,
+}
+
+pub trait Solution: Sized + 'static {
+    const DAY: u8;
+    type Parsed;
+    type Answer: fmt::Display;
+    type Error: fmt::Display;
+    /// Variants of part 1 & part 2, the first one of each being the one run by default
+    const PARTS: [&'static [Variant<Self>]; 2];
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error>;
+}
+
+/// Variants named after the functions implementing them, which either give the answer, or with
+/// `fallible:`, a result with the answer. Brute force variants go after `; slow:`.
+macro_rules! variants {
+    (fallible: $($variant:ident),+ $(; slow: $($slow:ident),+)?) => {
+        &[
+            $($crate::solution::Variant {
+                name: stringify!($variant),
+                solve: |parsed| $variant(parsed).map(Into::into),
+                slow: false,
+            },)+
+            $($($crate::solution::Variant {
+                name: stringify!($slow),
+                solve: |parsed| $slow(parsed).map(Into::into),
+                slow: true,
+            },)+)?
+        ]
+    };
+    ($($variant:ident),+ $(; slow: $($slow:ident),+)?) => {
+        &[
+            $($crate::solution::Variant {
+                name: stringify!($variant),
+                solve: |parsed| Ok($variant(parsed).into()),
+                slow: false,
+            },)+
+            $($($crate::solution::Variant {
+                name: stringify!($slow),
+                solve: |parsed| Ok($slow(parsed).into()),
+                slow: true,
+            },)+)?
+        ]
+    };
+}
+pub(crate) use variants;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VariantId {
+    pub day: u8,
+    pub part: u8,
+    pub name: &'static str,
+    pub slow: bool,
+}
+
+impl fmt::Display for VariantId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day{} part{} ({})", self.day, self.part, self.name)
+    }
+}
+
+/// A [`Solution`] taking & giving text, so that every day can go in the same registry
+trait AnySolution {
+    fn day(&self) -> u8;
+    fn variants(&self) -> Vec<VariantId>;
+    fn solve(&self, part: u8, input: &str, include: &dyn Fn(&VariantId) -> bool) -> VariantAnswers;
+}
+
+impl<S: Solution> AnySolution for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn variants(&self) -> Vec<VariantId> {
+        (1..)
+            .zip(S::PARTS)
+            .flat_map(|(part, variants)| {
+                variants.iter().map(move |variant| VariantId {
+                    day: S::DAY,
+                    part,
+                    name: variant.name,
+                    slow: variant.slow,
+                })
+            })
+            .collect()
+    }
+
+    fn solve(&self, part: u8, input: &str, include: &dyn Fn(&VariantId) -> bool) -> VariantAnswers {
+        let Some(variants) = part
+            .checked_sub(1)
+            .and_then(|index| S::PARTS.get(usize::from(index)))
+        else {
+            return Vec::new();
+        };
+        let variants = variants.iter().filter(|variant| {
+            include(&VariantId {
+                day: S::DAY,
+                part,
+                name: variant.name,
+                slow: variant.slow,
+            })
+        });
+        // Parsing once for all the variants, as they all share the parsed input
+        match S::parse(input) {
+            Ok(parsed) => variants
+                .map(|variant| {
+                    let answer = (variant.solve)(&parsed)
+                        .map(|answer| answer.to_string())
+                        .map_err(|error| error.to_string());
+                    (variant.name, answer)
+                })
+                .collect(),
+            Err(error) => variants
+                .map(|variant| (variant.name, Err(error.to_string())))
+                .collect(),
+        }
+    }
+}
+
+/// Every day solved so far, in order
+pub struct Registry {
+    solutions: Vec<Box<dyn AnySolution>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Registry {
+            solutions: vec![
+                Box::new(day1::Puzzle),
+                Box::new(day2::Puzzle),
+                Box::new(day3::Puzzle),
+                Box::new(day4::Puzzle),
+                Box::new(day5::Puzzle),
+                Box::new(day6::Puzzle),
+                Box::new(day7::Puzzle),
+                Box::new(day8::Puzzle),
+                Box::new(day9::Puzzle),
+                Box::new(day10::Puzzle),
+            ],
+        }
+    }
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Days that have a solution, in order
+    pub fn days(&self) -> impl Iterator<Item = u8> + '_ {
+        self.solutions.iter().map(|solution| solution.day())
+    }
+
+    /// Every variant of every part of every day, in order, with the default variant of each part
+    /// coming first
+    pub fn variants(&self) -> impl Iterator<Item = VariantId> + '_ {
+        self.solutions
+            .iter()
+            .flat_map(|solution| solution.variants())
+    }
+
+    /// Answers of the variants of a part that `include` accepts, in order, or none for an unknown
+    /// day or part
+    pub fn solve(
+        &self,
+        day: u8,
+        part: u8,
+        input: &str,
+        include: impl Fn(&VariantId) -> bool,
+    ) -> VariantAnswers {
+        self.solutions
+            .iter()
+            .find(|solution| solution.day() == day)
+            .map(|solution| solution.solve(part, input, &include))
+            .unwrap_or_default()
+    }
+
+    /// Answer of a single variant, or `None` if there's no such variant
+    pub fn solve_variant(&self, id: &VariantId, input: &str) -> Option<Result<String, String>> {
+        self.solve(id.day, id.part, input, |variant| variant.name == id.name)
+            .pop()
+            .map(|(_, answer)| answer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn lists_variants_in_order() {
+        let registry = Registry::new();
+        assert_eq!(
+            registry.days().collect::<Vec<_>>(),
+            (1..=10).collect::<Vec<_>>()
+        );
+        let day6 = registry
+            .variants()
+            .filter(|variant| variant.day == 6)
+            .map(|variant| format!("{variant}"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            day6,
+            [
+                "day6 part1 (part1)",
+                "day6 part1 (part1_binary)",
+                "day6 part1 (part1_closed_form)",
+                "day6 part2 (part2)",
+                "day6 part2 (part2_binary)",
+                "day6 part2 (part2_closed_form)",
+            ]
+        );
+        assert!(registry
+            .variants()
+            .any(|variant| variant.name == "part2_naive" && variant.slow));
+    }
+
+    #[test]
+    fn solves_with_chosen_variants() {
+        let registry = Registry::new();
+        let input = indoc! {"
+            Time:      7  15   30
+            Distance:  9  40  200
+        "};
+        assert_eq!(
+            registry.solve(6, 1, input, |_| true),
+            vec![
+                ("part1", Ok("288".to_string())),
+                ("part1_binary", Ok("288".to_string())),
+                ("part1_closed_form", Ok("288".to_string()))
+            ]
+        );
+        let binary = registry
+            .variants()
+            .find(|variant| {
+                variant.day == 6 && variant.part == 2 && variant.name.ends_with("binary")
+            })
+            .expect("day 6 has a binary search variant");
+        assert_eq!(
+            registry.solve_variant(&binary, input),
+            Some(Ok("71503".to_string()))
+        );
+        assert_eq!(
+            registry.solve(6, 1, "Time: 7", |_| true)[0].1,
+            Err("line 2, column 1: expected the line to start with `Distance:`".to_string())
+        );
+        assert!(registry.solve(6, 3, input, |_| true).is_empty());
+        assert!(registry.solve(25, 1, input, |_| true).is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+//! Regression checks against the accepted answers in `answers.toml`, using inputs from the local
+//! cache only:
+//! - `cargo test verify_answers -- --nocapture` recomputes every registered part with all its variants
+//! - `cargo test record_answers -- --ignored` accepts the current answers of unregistered parts
+
+use crate::{
+    answers::{AnswerRegistry, VariantAnswers, DEFAULT_ANSWERS_FILE},
+    input::{Config, InputStore},
+    solution::Registry,
+};
+use std::path::Path;
+
+const YEAR: u16 = 2023;
+
+/// Answers of every variant of a part, apart from the slow ones that don't complete on actual
+/// inputs
+fn solve(registry: &Registry, day: u8, part: u8, input: &str) -> VariantAnswers {
+    registry.solve(day, part, input, |variant| !variant.slow)
+}
+
+fn offline_inputs() -> InputStore {
+    let config = Config::load().unwrap_or_else(|error| panic!("{error}"));
+    InputStore::offline(config.cache_dir)
+}
+
+#[test]
+fn verify_answers() {
+    let registry = AnswerRegistry::load(Path::new(DEFAULT_ANSWERS_FILE))
+        .unwrap_or_else(|error| panic!("{error}"));
+    let solutions = Registry::new();
+    let checks = registry.verify(YEAR, &offline_inputs(), |day, part, input| {
+        solve(&solutions, day, part, input)
+    });
+    for check in &checks {
+        println!("{check}");
+    }
+    let failures = checks
+        .iter()
+        .filter(|check| check.outcome.is_failure())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+#[ignore = "writes to answers.toml, run explicitly to accept new answers"]
+fn record_answers() {
+    let path = Path::new(DEFAULT_ANSWERS_FILE);
+    let mut registry = AnswerRegistry::load(path).unwrap_or_else(|error| panic!("{error}"));
+    let inputs = offline_inputs();
+    let solutions = Registry::new();
+    for day in solutions.days() {
+        let Ok(input) = inputs.get(YEAR, day) else {
+            continue;
+        };
+        for part in 1..=2 {
+            if registry.get(day, part).is_some() {
+                continue;
+            }
+            // The first variant is the one run by default, so it's the one whose answer is accepted
+            if let Some((variant, Ok(answer))) =
+                solve(&solutions, day, part, &input).into_iter().next()
+            {
+                println!("day{day} part{part} ({variant}): accepting {answer}");
+                registry.record(day, part, answer, &input);
+            }
+        }
+    }
+    registry
+        .save(path)
+        .unwrap_or_else(|error| panic!("{error}"));
+}